@@ -1,14 +1,30 @@
-use std::{collections::HashSet, marker::PhantomData};
+use std::{collections::BTreeMap, marker::PhantomData};
 
 use anyhow::{bail, Context, Result};
-use async_graphql::{InputObject, SimpleObject};
+use async_graphql::{Enum, InputObject, SimpleObject};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
-use crate::{declare_id_type, docker::ExistingContainer, utils::time::get_now};
+use crate::{
+    declare_id_type,
+    docker::{ExistingContainer, Port, NAME_PREFIX},
+    utils::time::get_now,
+};
 
-use super::{containers::AppContainer, existing_containers::ExistingAppContainer, AppTemplate};
+use super::{
+    app_deps::AppDependency,
+    containers::AppContainer,
+    deps::compute_dependency_levels,
+    encryption::{SecretsKey, REDACTED_VALUE},
+    existing_containers::ExistingAppContainer,
+    origin::{template_origin, AppTemplateOrigin, AppTemplateSource},
+    references::{parse_references, rename_container_references, resolve_references, EnvReference},
+    secrets::{generate_secrets, inject_secrets, referenced_secrets, AppSecret},
+    volumes::check_shared_volumes,
+    AppContainerTemplate, AppMetadata, AppSharedVolume, AppTemplate, AppVolumeType,
+    ContainerEnvironmentVar, NAME_VALIDATOR,
+};
 
 #[derive(SimpleObject, Serialize, Deserialize, Clone)]
 #[graphql(complex)]
@@ -16,25 +32,103 @@ pub struct App {
     pub id: AppId,
     pub name: String,
     pub containers: Vec<AppContainer>,
-    created_on: OffsetDateTime,
+
+    /// Volumes which can be mounted by several of the application's containers
+    #[serde(default)]
+    pub shared_volumes: Vec<AppSharedVolume>,
+
+    /// Applications this one depends on
+    #[serde(default)]
+    pub dependencies: Vec<AppDependency>,
+
+    /// State the reconciliation loop keeps the application in, if managed
+    #[serde(default)]
+    pub desired_state: Option<AppDesiredState>,
+
+    /// Name of the Docker host the application runs on
+    ///
+    /// Empty for applications saved before Docker hosts were introduced, until they are loaded.
+    #[serde(default)]
+    pub host: String,
+
+    /// Secrets generated when the application was created
+    #[graphql(skip)]
+    #[serde(default)]
+    pub secrets: Vec<AppSecret>,
+
+    /// Template the application was created from
+    #[serde(default)]
+    pub template_origin: Option<AppTemplateOrigin>,
+
+    /// Tags used to organize applications
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Group (or folder) the application belongs to
+    #[serde(default)]
+    pub group: Option<String>,
+
+    /// Informations displayed about the application
+    #[serde(default)]
+    pub metadata: AppMetadata,
+
+    pub(super) created_on: OffsetDateTime,
 }
 
 impl App {
-    pub fn new(input: AppTemplate) -> Result<Self> {
-        if input.name.trim().is_empty() {
-            bail!("Please provide a non-empty name");
-        }
+    pub fn new(
+        input: AppTemplate,
+        host: String,
+        source: Option<AppTemplateSource>,
+        key: &SecretsKey,
+    ) -> Result<Self> {
+        validate_app_name(&input.name)?;
 
         #[deny(unused_variables)]
-        let AppTemplate { name, containers } = input;
+        let AppTemplate {
+            name,
+            id,
+            version,
+            containers,
+            shared_volumes,
+            metadata,
+            secrets,
+            params: _,
+        } = input;
+
+        let shared_volumes = shared_volumes.unwrap_or_default();
+        check_shared_volumes(&shared_volumes, &containers)?;
+
+        let metadata = metadata.unwrap_or_default();
+
+        metadata
+            .validate(&containers)
+            .context("Invalid application metadata")?;
 
         let mut app = Self {
             id: AppId(rand::thread_rng().gen()),
+            template_origin: template_origin(id, version, source)?,
+            tags: vec![],
+            group: None,
+            dependencies: vec![],
+            desired_state: None,
+            metadata,
+            secrets: generate_secrets(secrets.unwrap_or_default(), &[], key)
+                .with_context(|| format!("Failed to generate secrets for application '{name}'"))?,
             name,
             containers: vec![],
+            shared_volumes,
+            host,
             created_on: get_now(),
         };
 
+        let containers = containers
+            .into_iter()
+            .map(|input| app.seal_env_vars(input, None, key))
+            .collect::<Result<Vec<_>>>()?;
+
+        app.check_env_references(&containers)?;
+
         let containers = containers
             .into_iter()
             .map(|input| AppContainer::new(app.identity(), input))
@@ -45,9 +139,45 @@ impl App {
 
         app.add_containers(containers)?;
 
+        let installed_env_vars = app.env_vars_by_container();
+
+        if let Some(origin) = &mut app.template_origin {
+            origin.installed_env_vars = installed_env_vars;
+        }
+
         Ok(app)
     }
 
+    pub fn set_tags(&mut self, tags: Vec<String>) -> Result<()> {
+        let mut tags = tags
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .collect::<Vec<_>>();
+
+        if tags.iter().any(|tag| tag.is_empty()) {
+            bail!("Please provide non-empty tags");
+        }
+
+        tags.sort();
+        tags.dedup();
+
+        self.tags = tags;
+
+        Ok(())
+    }
+
+    pub fn set_group(&mut self, group: Option<String>) -> Result<()> {
+        let group = group.map(|group| group.trim().to_string());
+
+        if group.as_ref().is_some_and(|group| group.is_empty()) {
+            bail!("Please provide a non-empty group name");
+        }
+
+        self.group = group;
+
+        Ok(())
+    }
+
     pub fn identity(&self) -> AppIdentity {
         AppIdentity {
             id: self.id,
@@ -56,6 +186,11 @@ impl App {
         }
     }
 
+    /// Name of the Docker network the application's containers reach each other on
+    pub fn network_name(&self) -> String {
+        format!("{NAME_PREFIX}{}", self.id.encode())
+    }
+
     pub fn get_container(&self, name: &str) -> Option<&AppContainer> {
         self.containers
             .iter()
@@ -63,37 +198,580 @@ impl App {
     }
 
     pub fn add_containers(&mut self, containers: Vec<AppContainer>) -> Result<()> {
-        let container_names = self
-            .containers
-            .iter()
-            .chain(containers.iter())
-            .map(|container| container.name.clone())
-            .collect::<HashSet<_>>();
+        let mut all_containers = self.containers.clone();
 
         for container in containers {
-            if self.get_container(&container.name).is_some() {
+            if all_containers.iter().any(|c| c.name == container.name) {
                 bail!(
                     "This application already has a container with the '{}' name",
                     container.name
                 );
             }
 
-            for dep in &container.depends_on {
-                if !container_names.contains(dep) {
-                    bail!(
-                        "Container '{}' depends on unknown container '{}'",
-                        container.name,
-                        dep
-                    );
+            all_containers.push(container);
+        }
+
+        compute_dependency_levels(&all_containers)
+            .with_context(|| format!("Invalid dependencies in application '{}'", self.name))?;
+
+        self.containers = all_containers;
+
+        Ok(())
+    }
+
+    /// Get the application's containers grouped by dependency levels
+    pub fn dependency_levels(&self) -> Result<Vec<Vec<&AppContainer>>> {
+        compute_dependency_levels(&self.containers)
+    }
+
+    /// Get the application's containers sorted so that each container comes after its dependencies
+    pub fn containers_by_deps(&self) -> Result<Vec<&AppContainer>> {
+        Ok(self.dependency_levels()?.into_iter().flatten().collect())
+    }
+
+    /// Replace secrets references in a value with the application's secrets
+    pub fn inject_secrets(&self, value: &str, key: &SecretsKey) -> Result<String> {
+        inject_secrets(value, &self.secrets, key)
+    }
+
+    /// Replace references to the application and its containers in a value
+    pub fn resolve_references(&self, value: &str) -> Result<String> {
+        resolve_references(value, |reference| match reference {
+            EnvReference::AppId => Ok(self.id.to_string()),
+            EnvReference::AppName => Ok(self.name.clone()),
+
+            EnvReference::ContainerHostname(name) => self
+                .get_container(name)
+                .map(AppContainer::docker_container_name)
+                .with_context(|| format!("Reference to unknown container '{name}'")),
+
+            EnvReference::ContainerPort(name, port) => self
+                .get_container(name)
+                .map(|_| port.to_string())
+                .with_context(|| format!("Reference to unknown container '{name}'")),
+
+            EnvReference::ContainerHostPort(name, port) => self
+                .get_container(name)
+                .with_context(|| format!("Reference to unknown container '{name}'"))?
+                .port_bindings
+                .iter()
+                .find(|binding| binding.container_port.port == *port)
+                .map(|binding| binding.host_port.port.to_string())
+                .with_context(|| format!("Container '{name}' has no binding for port {port}")),
+        })
+    }
+
+    /// Ensure the secrets and containers referenced in environment variables exist
+    fn check_env_references(&self, containers: &[AppContainerTemplate]) -> Result<()> {
+        for container in containers {
+            // Secret values are used as is
+            for var in container
+                .env_vars
+                .iter()
+                .filter(|var| var.secret != Some(true))
+            {
+                for secret in referenced_secrets(&var.value) {
+                    if !self.secrets.iter().any(|s| s.declaration.name == secret) {
+                        bail!(
+                            "Environment variable '{}' of container '{}' references unknown secret '{secret}'",
+                            var.name,
+                            container.name
+                        );
+                    }
                 }
-            }
 
-            self.containers.push(container.clone());
+                let references = parse_references(&var.value).with_context(|| {
+                    format!(
+                        "Invalid reference in environment variable '{}' of container '{}'",
+                        var.name, container.name
+                    )
+                })?;
+
+                for reference in references {
+                    let (name, port) = match reference {
+                        EnvReference::AppId | EnvReference::AppName => continue,
+                        EnvReference::ContainerHostname(name)
+                        | EnvReference::ContainerPort(name, _) => (name, None),
+                        EnvReference::ContainerHostPort(name, port) => (name, Some(port)),
+                    };
+
+                    let Some(target) = containers.iter().find(|c| c.name == name) else {
+                        bail!(
+                            "Environment variable '{}' of container '{}' references unknown container '{name}'",
+                            var.name,
+                            container.name
+                        );
+                    };
+
+                    if let Some(port) = port {
+                        if !target
+                            .port_bindings
+                            .iter()
+                            .any(|binding| binding.container_port.port == port)
+                        {
+                            bail!(
+                                "Environment variable '{}' of container '{}' references port {port} of container '{name}', which is not bound",
+                                var.name,
+                                container.name
+                            );
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// Encrypt the value of a container's secret environment variables
+    ///
+    /// Provided values are always considered as plain ones, whatever they look like.
+    /// Values that did not change keep their existing encrypted form,
+    /// so the container isn't considered as changed when updating the application.
+    /// Secrets whose value is the redaction placeholder keep their existing value.
+    fn seal_env_vars(
+        &self,
+        mut input: AppContainerTemplate,
+        existing: Option<&AppContainer>,
+        key: &SecretsKey,
+    ) -> Result<AppContainerTemplate> {
+        for var in &mut input.env_vars {
+            if var.secret != Some(true) {
+                continue;
+            }
+
+            let existing_value = existing
+                .and_then(|container| {
+                    container
+                        .env_vars
+                        .iter()
+                        .find(|other| other.name == var.name && other.secret == Some(true))
+                })
+                .map(|other| other.value.clone());
+
+            if var.value == REDACTED_VALUE {
+                var.value = existing_value.with_context(|| {
+                    format!(
+                        "Please provide a value for secret environment variable '{}' of container '{}'",
+                        var.name, input.name
+                    )
+                })?;
+
+                continue;
+            }
+
+            if var.value.trim().is_empty() {
+                bail!(
+                    "Please provide a value for the '{}' environment variable or remove this variable",
+                    var.name
+                );
+            }
+
+            var.value = match existing_value {
+                Some(existing_value) if key.decrypt(&existing_value)? == var.value => {
+                    existing_value
+                }
+                _ => key.encrypt(&var.value)?,
+            };
+        }
+
+        Ok(input)
+    }
+
+    /// Build an updated version of this application from a template
+    ///
+    /// Containers are matched by name: unchanged containers are kept as is,
+    /// while changed containers keep their ID (and so their volumes).
+    /// Secrets that already exist keep their value, new ones are generated.
+    pub fn update(&self, template: AppTemplate, key: &SecretsKey) -> Result<(Self, AppUpdateDiff)> {
+        #[deny(unused_variables)]
+        let AppTemplate {
+            name,
+            containers,
+            shared_volumes,
+            metadata,
+            secrets,
+            id: _,
+            version: _,
+            params: _,
+        } = template;
+
+        if name != self.name {
+            bail!("Application cannot be renamed while being updated");
+        }
+
+        let shared_volumes = shared_volumes.unwrap_or_default();
+        check_shared_volumes(&shared_volumes, &containers)?;
+
+        let metadata = metadata.unwrap_or_default();
+
+        metadata
+            .validate(&containers)
+            .context("Invalid application metadata")?;
+
+        let mut app = Self {
+            containers: vec![],
+            shared_volumes,
+            metadata,
+            secrets: generate_secrets(secrets.unwrap_or_default(), &self.secrets, key)
+                .with_context(|| format!("Failed to generate secrets for application '{name}'"))?,
+            ..self.clone()
+        };
+
+        let containers = containers
+            .into_iter()
+            .map(|input| {
+                let existing = self.get_container(&input.name);
+                app.seal_env_vars(input, existing, key)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        app.check_env_references(&containers)?;
+
+        let mut diff = AppUpdateDiff::default();
+
+        let removed = self
+            .containers
+            .iter()
+            .filter(|container| !containers.iter().any(|input| input.name == container.name))
+            .map(|container| container.name.clone())
+            .collect();
+
+        let containers = containers
+            .into_iter()
+            .map(|input| {
+                let existing = self.get_container(&input.name);
+
+                match existing {
+                    Some(existing) if existing.clone().to_template() == input => {
+                        diff.unchanged.push(input.name);
+                        Ok(existing.clone())
+                    }
+
+                    Some(existing) => {
+                        diff.changed.push(input.name.clone());
+                        existing.update(input)
+                    }
+
+                    None => {
+                        diff.added.push(input.name.clone());
+                        AppContainer::new(app.identity(), input)
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| {
+                format!("Failed to update containers for application '{}'", app.name)
+            })?;
+
+        diff.removed = removed;
+
+        // Containers mounting a shared volume whose declaration changed must be re-created
+        for container in &containers {
+            let mounts_changed_volume =
+                container
+                    .volumes
+                    .iter()
+                    .any(|volume| match &volume.variant {
+                        AppVolumeType::Shared { shared_volume, .. } => {
+                            self.get_shared_volume(shared_volume)
+                                != app.get_shared_volume(shared_volume)
+                        }
+                        _ => false,
+                    });
+
+            if mounts_changed_volume && diff.unchanged.contains(&container.name) {
+                diff.unchanged.retain(|name| name != &container.name);
+                diff.changed.push(container.name.clone());
+            }
+        }
+
+        app.add_containers(containers)?;
+
+        Ok((app, diff))
+    }
+
+    /// Build a copy of this application with a new name and fresh identifiers
+    ///
+    /// Host ports are remapped to the first following ports which are free according to the provided function.
+    /// Dependencies are kept as the containers still reference them, and so are tags and metadata.
+    /// The copy is not considered as installed from a template, so it can't be upgraded.
+    pub fn duplicate(&self, name: String, is_port_free: impl Fn(Port) -> bool) -> Result<Self> {
+        validate_app_name(&name)?;
+
+        let mut app = Self {
+            id: AppId(rand::thread_rng().gen()),
+            name,
+            containers: vec![],
+            // Installed parameters and values would be re-applied to the copy when upgrading it
+            template_origin: None,
+            // Clones are not managed until requested, as they may not be expected to run yet
+            desired_state: None,
+            created_on: get_now(),
+            ..self.clone()
+        };
+
+        let mut taken = Vec::<Port>::new();
+
+        let containers = self
+            .containers
+            .iter()
+            .map(|container| {
+                let mut container = container.duplicate(app.identity());
+
+                for binding in &mut container.port_bindings {
+                    let original = binding.host_port;
+
+                    binding.host_port = (original.port..=u16::MAX)
+                        .map(|port| Port {
+                            port,
+                            port_type: original.port_type,
+                        })
+                        .find(|&port| {
+                            !taken.iter().any(|other| other.overlaps(port)) && is_port_free(port)
+                        })
+                        .with_context(|| {
+                            format!(
+                                "Failed to find a free port to remap port {original} of container '{}'",
+                                container.name
+                            )
+                        })?;
+
+                    taken.push(binding.host_port);
+                }
+
+                Ok(container)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        app.containers = containers;
+
+        Ok(app)
+    }
+
+    /// Build an upgraded version of this application from a newer version of its template
+    ///
+    /// Environment variables edited or added by the user since the template was installed are kept.
+    pub fn upgrade(
+        &self,
+        mut template: AppTemplate,
+        source: AppTemplateSource,
+        key: &SecretsKey,
+    ) -> Result<(Self, AppUpdateDiff)> {
+        let origin = self
+            .template_origin
+            .as_ref()
+            .context("Application was not created from a template")?;
+
+        let new_origin =
+            template_origin(template.id.take(), template.version.take(), Some(source))?
+                .context("New template has no ID or version")?;
+
+        if new_origin.template_id != origin.template_id {
+            bail!(
+                "Cannot upgrade application from template '{}' to template '{}'",
+                origin.template_id,
+                new_origin.template_id
+            );
+        }
+
+        template.name = self.name.clone();
+
+        let mut user_edited = vec![];
+
+        for container in &self.containers {
+            let installed = origin.installed_env_vars.get(&container.name);
+
+            for var in &container.env_vars {
+                let installed_var =
+                    installed.and_then(|vars| vars.iter().find(|other| other.name == var.name));
+
+                if installed_var.is_some_and(|installed_var| installed_var.value == var.value) {
+                    continue;
+                }
+
+                user_edited.push((container.name.as_str(), var, installed_var));
+
+                if let Some(new_container) = template
+                    .containers
+                    .iter_mut()
+                    .find(|c| c.name == container.name)
+                {
+                    new_container
+                        .env_vars
+                        .retain(|other| other.name != var.name);
+
+                    // Secret values are sealed again, the placeholder keeps their existing value
+                    new_container.env_vars.push(if var.secret == Some(true) {
+                        ContainerEnvironmentVar {
+                            value: REDACTED_VALUE.to_string(),
+                            ..var.clone()
+                        }
+                    } else {
+                        var.clone()
+                    });
+                }
+            }
+        }
+
+        let (mut app, diff) = self.update(template, key)?;
+
+        // Edited values are compared to the originally installed ones, so they are kept on later upgrades too
+        let mut installed_env_vars = app.env_vars_by_container();
+
+        for (container_name, env_vars) in installed_env_vars.iter_mut() {
+            env_vars.retain_mut(|var| {
+                match user_edited
+                    .iter()
+                    .find(|(c, edited, _)| c == container_name && edited.name == var.name)
+                {
+                    None => true,
+                    Some((_, _, None)) => false,
+                    Some((_, _, Some(installed_var))) => {
+                        *var = (*installed_var).clone();
+                        true
+                    }
+                }
+            });
+        }
+
+        app.template_origin = Some(AppTemplateOrigin {
+            installed_env_vars,
+            ..new_origin
+        });
+
+        Ok((app, diff))
+    }
+
+    /// Build a renamed version of this application
+    ///
+    /// All containers must be re-created as their labels contain the application's name.
+    pub fn renamed(&self, name: String, apps: &[App]) -> Result<(Self, AppUpdateDiff)> {
+        validate_app_name(&name)?;
+
+        if apps.iter().any(|app| app.name == name && app.id != self.id) {
+            bail!("An application already exists with the provided name");
+        }
+
+        let mut app = Self {
+            name,
+            ..self.clone()
+        };
+
+        let identity = app.identity();
+
+        for container in &mut app.containers {
+            container.app = identity.clone();
+        }
+
+        let diff = AppUpdateDiff {
+            changed: app.containers.iter().map(|c| c.name.clone()).collect(),
+            ..Default::default()
+        };
+
+        Ok((app, diff))
+    }
+
+    /// Build a version of this application with a renamed container
+    ///
+    /// Dependencies and references to the container in environment variables are updated as well.
+    pub fn with_renamed_container(
+        &self,
+        name: &str,
+        new_name: String,
+    ) -> Result<(Self, AppUpdateDiff)> {
+        if self.get_container(name).is_none() {
+            bail!("Container '{name}' was not found");
+        }
+
+        if !NAME_VALIDATOR.is_match(&new_name) {
+            bail!(
+                "Invalid container name provided, please follow regex: {}",
+                NAME_VALIDATOR.as_str()
+            );
+        }
+
+        if self.get_container(&new_name).is_some() {
+            bail!("This application already has a container with the '{new_name}' name");
+        }
+
+        let mut app = self.clone();
+
+        for container in &mut app.containers {
+            let mut installed = app
+                .template_origin
+                .as_mut()
+                .and_then(|origin| origin.installed_env_vars.get_mut(&container.name));
+
+            if container.name == name {
+                container.name = new_name.clone();
+            }
+
+            for dep in &mut container.depends_on {
+                if dep == name {
+                    *dep = new_name.clone();
+                }
+            }
+
+            // Secret values are used as is, so they contain no references
+            for var in container
+                .env_vars
+                .iter_mut()
+                .filter(|var| var.secret != Some(true))
+            {
+                let renamed = rename_container_references(&var.value, name, &new_name);
+
+                if renamed == var.value {
+                    continue;
+                }
+
+                // Values the user didn't edit must still be detected as such when upgrading
+                if let Some(installed) = installed.as_deref_mut() {
+                    for installed_var in installed.iter_mut() {
+                        if installed_var.name == var.name && installed_var.value == var.value {
+                            installed_var.value = renamed.clone();
+                        }
+                    }
+                }
+
+                var.value = renamed;
+            }
+        }
+
+        if let Some(web_ui) = &mut app.metadata.web_ui {
+            if web_ui.container == name {
+                web_ui.container = new_name.clone();
+            }
+        }
+
+        if let Some(origin) = &mut app.template_origin {
+            if let Some(env_vars) = origin.installed_env_vars.remove(name) {
+                origin.installed_env_vars.insert(new_name.clone(), env_vars);
+            }
+        }
+
+        let diff = AppUpdateDiff {
+            removed: vec![name.to_string()],
+            added: vec![new_name.clone()],
+            unchanged: app
+                .containers
+                .iter()
+                .filter(|c| c.name != new_name)
+                .map(|c| c.name.clone())
+                .collect(),
+            ..Default::default()
+        };
+
+        Ok((app, diff))
+    }
+
+    fn env_vars_by_container(&self) -> BTreeMap<String, Vec<ContainerEnvironmentVar>> {
+        self.containers
+            .iter()
+            .map(|container| (container.name.clone(), container.env_vars.clone()))
+            .collect()
+    }
+
     pub fn decode_container(
         &self,
         container: ExistingContainer,
@@ -106,26 +784,130 @@ impl App {
         }
     }
 
+    /// Export the application as a template
+    ///
+    /// Values of secret environment variables are replaced by the redaction placeholder,
+    /// as they can only be decrypted by this server: they must be provided again when importing the template.
     pub fn to_template(self) -> AppTemplate {
         #[deny(unused_variables)]
         let Self {
             name,
             containers,
+            shared_volumes,
+            secrets,
+            template_origin,
+            metadata,
 
             id: _,
+            host: _,
+            tags: _,
+            group: _,
+            dependencies: _,
+            desired_state: _,
             created_on: _,
         } = self;
 
+        let (id, version) = match template_origin {
+            Some(origin) => (Some(origin.template_id), Some(origin.version)),
+            None => (None, None),
+        };
+
         AppTemplate {
             name,
+            id,
+            version,
             containers: containers
                 .into_iter()
-                .map(AppContainer::to_template)
+                .map(|container| {
+                    let mut template = container.to_template();
+
+                    for var in &mut template.env_vars {
+                        if var.secret == Some(true) {
+                            var.value = REDACTED_VALUE.to_string();
+                        }
+                    }
+
+                    template
+                })
                 .collect(),
+            shared_volumes: if shared_volumes.is_empty() {
+                None
+            } else {
+                Some(shared_volumes)
+            },
+            metadata: if metadata.is_empty() {
+                None
+            } else {
+                Some(metadata)
+            },
+            params: None,
+            secrets: if secrets.is_empty() {
+                None
+            } else {
+                Some(
+                    secrets
+                        .into_iter()
+                        .map(|secret| secret.declaration)
+                        .collect(),
+                )
+            },
         }
     }
 }
 
+/// Ensure an application's name is non-empty and can be used in paths, as its data directory is named after it
+fn validate_app_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("Please provide a non-empty name");
+    }
+
+    if name.contains(['/', '\\', '\0']) {
+        bail!("Application names cannot contain slashes or null characters");
+    }
+
+    Ok(())
+}
+
+/// State an application should be kept in by the reconciliation loop
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AppDesiredState {
+    /// Containers are created and running
+    Running,
+
+    /// Containers are stopped (or not created)
+    Stopped,
+
+    /// Containers are not created
+    Absent,
+}
+
+/// Differences between an application and its updated version, by container name
+#[derive(Default)]
+pub struct AppUpdateDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+impl AppUpdateDiff {
+    /// Check if a container's Docker container must be removed to apply the update
+    pub fn must_remove(&self, container_name: &str) -> bool {
+        self.removed
+            .iter()
+            .chain(&self.changed)
+            .any(|name| name == container_name)
+    }
+
+    /// Check if a container's Docker container must be created to apply the update
+    pub fn must_create(&self, container_name: &str) -> bool {
+        self.added
+            .iter()
+            .chain(&self.changed)
+            .any(|name| name == container_name)
+    }
+}
+
 declare_id_type!(AppId);
 
 #[derive(SimpleObject, InputObject, Serialize, Deserialize, Hash, Clone, PartialEq, Eq)]
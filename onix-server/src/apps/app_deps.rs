@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use crate::docker::NAME_PREFIX;
+
+use super::{App, AppDesiredState, AppId};
+
+/// Dependency of an application on another one, which must be running for it to work
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppDependency {
+    pub app_id: AppId,
+
+    /// Connect the application's containers to a network shared by the dependency,
+    /// where the dependency's containers can be reached using their name as hostname
+    pub shared_network: Option<bool>,
+}
+
+impl App {
+    /// Name of the Docker network the application's containers are reachable on by its dependents
+    pub fn shared_network_name(&self) -> String {
+        shared_network_name(self.id)
+    }
+
+    /// Check if any application depending on this one shares its network
+    pub fn is_exposed_on_shared_network(&self, apps: &[App]) -> bool {
+        apps.iter().any(|other| {
+            other
+                .dependencies
+                .iter()
+                .any(|dep| dep.app_id == self.id && dep.shared_network == Some(true))
+        })
+    }
+
+    /// Networks the application's containers must be connected to, to reach their dependencies
+    pub fn dependencies_networks(&self) -> Vec<String> {
+        self.dependencies
+            .iter()
+            .filter(|dep| dep.shared_network == Some(true))
+            .map(|dep| shared_network_name(dep.app_id))
+            .collect()
+    }
+}
+
+fn shared_network_name(id: AppId) -> String {
+    format!("{NAME_PREFIX}{}_shared", id.encode())
+}
+
+/// Ensure dependencies between applications are valid, and don't form any cycle
+///
+/// Applications can only depend on other applications of the same Docker host.
+pub fn check_app_dependencies(apps: &[App]) -> Result<()> {
+    for app in apps {
+        for (i, dep) in app.dependencies.iter().enumerate() {
+            if dep.app_id == app.id {
+                bail!("Application '{}' cannot depend on itself", app.name);
+            }
+
+            if app.dependencies[..i]
+                .iter()
+                .any(|other| other.app_id == dep.app_id)
+            {
+                bail!(
+                    "Application '{}' declares the same dependency twice",
+                    app.name
+                );
+            }
+
+            let dep_app = find_app(apps, dep.app_id).with_context(|| {
+                format!(
+                    "Application '{}' depends on an unknown application",
+                    app.name
+                )
+            })?;
+
+            if dep_app.host != app.host {
+                bail!(
+                    "Application '{}' cannot depend on application '{}' as they are on different Docker hosts",
+                    app.name,
+                    dep_app.name
+                );
+            }
+        }
+    }
+
+    let mut visited = HashSet::new();
+
+    for app in apps {
+        if let Some(cycle) = find_cycle(apps, app, &mut vec![], &mut visited) {
+            bail!("Dependency cycle detected: {}", cycle.join(" -> "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Ensure no application is desired to be running while one of its dependencies, direct or not, is desired not to be
+pub fn check_desired_states(apps: &[App]) -> Result<()> {
+    for app in apps
+        .iter()
+        .filter(|app| app.desired_state == Some(AppDesiredState::Running))
+    {
+        for dep in sorted_app_dependencies(apps, app.id)? {
+            if let Some(desired @ (AppDesiredState::Stopped | AppDesiredState::Absent)) =
+                dep.desired_state
+            {
+                bail!(
+                    "Application '{}' is desired to be running, but depends on application '{}' which is desired to be {desired:?}",
+                    app.name,
+                    dep.name
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get all applications the provided one depends on, directly or not, in the order they must be started in
+pub fn sorted_app_dependencies(apps: &[App], id: AppId) -> Result<Vec<&App>> {
+    let app = find_app(apps, id).context("Provided application ID was not found")?;
+
+    let mut sorted = vec![];
+    visit_post_order(app, &mut HashSet::new(), &mut sorted, &|app| {
+        app.dependencies
+            .iter()
+            .filter_map(|dep| find_app(apps, dep.app_id))
+            .collect()
+    });
+
+    sorted.pop();
+
+    Ok(sorted)
+}
+
+/// Get all applications depending on the provided one, directly or not, in the order they must be stopped in
+pub fn sorted_app_dependents(apps: &[App], id: AppId) -> Result<Vec<&App>> {
+    let app = find_app(apps, id).context("Provided application ID was not found")?;
+
+    let mut sorted = vec![];
+    visit_post_order(app, &mut HashSet::new(), &mut sorted, &|app| {
+        apps.iter()
+            .filter(|other| other.dependencies.iter().any(|dep| dep.app_id == app.id))
+            .collect()
+    });
+
+    sorted.pop();
+
+    Ok(sorted)
+}
+
+fn find_app(apps: &[App], id: AppId) -> Option<&App> {
+    apps.iter().find(|app| app.id == id)
+}
+
+/// Visit the graph in post-order, so each application comes after the ones it links to
+///
+/// The provided application is the last one of the list.
+fn visit_post_order<'a>(
+    current: &'a App,
+    visited: &mut HashSet<AppId>,
+    sorted: &mut Vec<&'a App>,
+    linked: &impl Fn(&'a App) -> Vec<&'a App>,
+) {
+    if !visited.insert(current.id) {
+        return;
+    }
+
+    for other in linked(current) {
+        visit_post_order(other, visited, sorted, linked);
+    }
+
+    sorted.push(current);
+}
+
+/// Find a dependency cycle starting from an application
+///
+/// The returned path starts and ends with the same application's name
+fn find_cycle<'a>(
+    apps: &'a [App],
+    current: &'a App,
+    path: &mut Vec<&'a App>,
+    visited: &mut HashSet<AppId>,
+) -> Option<Vec<String>> {
+    if let Some(pos) = path.iter().position(|app| app.id == current.id) {
+        let mut cycle = path[pos..]
+            .iter()
+            .map(|app| app.name.clone())
+            .collect::<Vec<_>>();
+
+        cycle.push(current.name.clone());
+
+        return Some(cycle);
+    }
+
+    if !visited.insert(current.id) {
+        return None;
+    }
+
+    path.push(current);
+
+    for dep in &current.dependencies {
+        if let Some(dep) = find_app(apps, dep.app_id) {
+            if let Some(cycle) = find_cycle(apps, dep, path, visited) {
+                return Some(cycle);
+            }
+        }
+    }
+
+    path.pop();
+
+    None
+}
@@ -4,7 +4,7 @@ use std::{
     marker::PhantomData,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use async_graphql::SimpleObject;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -12,11 +12,14 @@ use time::OffsetDateTime;
 
 use crate::{
     declare_id_type,
-    docker::{ContainerEnvironmentVar, ContainerPortBinding, NAME_PREFIX},
+    docker::{
+        ContainerEnvironmentVar, ContainerNetworkMode, ContainerPortBinding, ContainerPrivileges,
+        NAME_PREFIX,
+    },
     utils::time::get_now,
 };
 
-use super::{app::AppIdentity, AppContainerTemplate, AppVolume, NAME_VALIDATOR};
+use super::{app::AppIdentity, AppContainerTemplate, AppVolume, AppVolumeType, NAME_VALIDATOR};
 
 #[derive(SimpleObject, Serialize, Deserialize, Clone)]
 #[graphql(complex)]
@@ -25,10 +28,16 @@ pub struct AppContainer {
     pub id: AppContainerId,
     pub name: String,
     pub image: String,
+
+    /// Secret values are encrypted
+    #[graphql(skip)]
     pub env_vars: Vec<ContainerEnvironmentVar>,
+
     pub port_bindings: Vec<ContainerPortBinding>,
     pub volumes: Vec<AppVolume>,
     pub depends_on: Vec<String>,
+    pub privileges: Option<ContainerPrivileges>,
+    pub stop_timeout: Option<u32>,
     created_on: OffsetDateTime,
 }
 
@@ -52,6 +61,7 @@ impl AppContainer {
         for ContainerEnvironmentVar {
             ref name,
             ref value,
+            secret: _,
         } in &input.env_vars
         {
             if name.is_empty() {
@@ -90,6 +100,33 @@ impl AppContainer {
             );
         }
 
+        if let Some(privileges) = &input.privileges {
+            privileges
+                .validate()
+                .with_context(|| format!("Invalid privileges for container '{}'", input.name))?;
+
+            if privileges.network_mode == ContainerNetworkMode::Host
+                && !input.port_bindings.is_empty()
+            {
+                bail!("Port bindings cannot be used when the container uses the host's network");
+            }
+        }
+
+        for volume in &input.volumes {
+            if let Some(ownership) = &volume.ownership {
+                if !matches!(volume.variant, AppVolumeType::External { .. }) {
+                    bail!(
+                        "Ownership can only be set on external volumes (volume '{}')",
+                        volume.name
+                    );
+                }
+
+                ownership
+                    .validate()
+                    .with_context(|| format!("Invalid ownership for volume '{}'", volume.name))?;
+            }
+        }
+
         #[deny(unused_variables)]
         let AppContainerTemplate {
             name,
@@ -98,6 +135,8 @@ impl AppContainer {
             port_bindings,
             volumes,
             depends_on,
+            privileges,
+            stop_timeout,
         } = input;
 
         Ok(Self {
@@ -109,10 +148,33 @@ impl AppContainer {
             port_bindings,
             volumes,
             depends_on,
+            privileges,
+            stop_timeout,
             created_on: get_now(),
         })
     }
 
+    /// Build an updated version of this container, keeping its identity
+    pub fn update(&self, input: AppContainerTemplate) -> Result<Self> {
+        let updated = Self::new(self.app.clone(), input)?;
+
+        Ok(Self {
+            id: self.id,
+            created_on: self.created_on,
+            ..updated
+        })
+    }
+
+    /// Build a copy of this container for another application, with a fresh identifier
+    pub fn duplicate(&self, app: AppIdentity) -> Self {
+        Self {
+            app,
+            id: AppContainerId(rand::thread_rng().gen()),
+            created_on: get_now(),
+            ..self.clone()
+        }
+    }
+
     pub fn identity(&self) -> AppContainerIdentity {
         AppContainerIdentity {
             id: self.id,
@@ -147,6 +209,8 @@ impl AppContainer {
             port_bindings,
             volumes,
             depends_on,
+            privileges,
+            stop_timeout,
 
             app: _,
             id: _,
@@ -160,6 +224,8 @@ impl AppContainer {
             port_bindings,
             volumes,
             depends_on,
+            privileges,
+            stop_timeout,
         }
     }
 }
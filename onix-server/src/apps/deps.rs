@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::SimpleObject;
+
+use super::containers::AppContainer;
+
+/// Sort containers by dependency levels
+///
+/// Each level only depends on containers from the previous levels, so the containers
+/// of a same level can be handled in parallel. Containers are sorted by name in each level.
+pub fn compute_dependency_levels(containers: &[AppContainer]) -> Result<Vec<Vec<&AppContainer>>> {
+    for container in containers {
+        for dep in &container.depends_on {
+            if dep == &container.name {
+                bail!("Container '{}' cannot depend on itself", container.name);
+            }
+
+            if !containers.iter().any(|c| &c.name == dep) {
+                bail!(
+                    "Container '{}' depends on unknown container '{}'",
+                    container.name,
+                    dep
+                );
+            }
+        }
+    }
+
+    let mut levels = vec![];
+    let mut placed = HashSet::new();
+    let mut remaining = containers.iter().collect::<Vec<_>>();
+
+    while !remaining.is_empty() {
+        let (mut level, rest): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|container| {
+            container
+                .depends_on
+                .iter()
+                .all(|dep| placed.contains(dep.as_str()))
+        });
+
+        if level.is_empty() {
+            let cycle = find_cycle(&rest)
+                .expect("Assertion error: no dependency cycle found in unsortable containers");
+
+            bail!("Dependency cycle detected: {}", cycle.join(" -> "));
+        }
+
+        level.sort_by(|a, b| a.name.cmp(&b.name));
+
+        placed.extend(level.iter().map(|container| container.name.as_str()));
+
+        levels.push(level);
+        remaining = rest;
+    }
+
+    Ok(levels)
+}
+
+/// Get all containers the provided one depends on, directly or not, sorted by dependency order
+pub fn transitive_dependencies<'a>(
+    containers: &'a [AppContainer],
+    name: &str,
+) -> Result<Vec<&'a AppContainer>> {
+    transitive_closure(containers, name, |container, other| {
+        container.depends_on.contains(&other.name)
+    })
+}
+
+/// Get all containers depending on the provided one, directly or not, sorted by dependency order
+pub fn transitive_dependents<'a>(
+    containers: &'a [AppContainer],
+    name: &str,
+) -> Result<Vec<&'a AppContainer>> {
+    transitive_closure(containers, name, |container, other| {
+        other.depends_on.contains(&container.name)
+    })
+}
+
+fn transitive_closure<'a>(
+    containers: &'a [AppContainer],
+    name: &str,
+    is_linked: impl Fn(&AppContainer, &AppContainer) -> bool,
+) -> Result<Vec<&'a AppContainer>> {
+    let mut found = HashSet::new();
+    let mut queue = vec![name];
+
+    while let Some(current) = queue.pop() {
+        let current = containers
+            .iter()
+            .find(|c| c.name == current)
+            .with_context(|| format!("Container '{current}' was not found"))?;
+
+        for other in containers {
+            if is_linked(current, other) && found.insert(other.name.as_str()) {
+                queue.push(&other.name);
+            }
+        }
+    }
+
+    Ok(compute_dependency_levels(containers)?
+        .into_iter()
+        .flatten()
+        .filter(|container| found.contains(container.name.as_str()))
+        .collect())
+}
+
+/// Find a dependency cycle between the provided containers
+///
+/// The returned path starts and ends with the same container's name
+fn find_cycle(containers: &[&AppContainer]) -> Option<Vec<String>> {
+    fn visit<'a>(
+        containers: &[&'a AppContainer],
+        current: &'a AppContainer,
+        path: &mut Vec<&'a str>,
+        visited: &mut HashSet<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(pos) = path.iter().position(|name| *name == current.name) {
+            let mut cycle = path[pos..]
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+
+            cycle.push(current.name.clone());
+
+            return Some(cycle);
+        }
+
+        if !visited.insert(&current.name) {
+            return None;
+        }
+
+        path.push(&current.name);
+
+        for dep in &current.depends_on {
+            if let Some(dep) = containers.iter().find(|c| &c.name == dep) {
+                if let Some(cycle) = visit(containers, dep, path, visited) {
+                    return Some(cycle);
+                }
+            }
+        }
+
+        path.pop();
+
+        None
+    }
+
+    let mut visited = HashSet::new();
+
+    containers
+        .iter()
+        .find_map(|container| visit(containers, container, &mut vec![], &mut visited))
+}
+
+/// Dependency graph of an application's containers
+#[derive(SimpleObject)]
+pub struct AppDependencyGraph {
+    /// Containers' names, in the order they are started in
+    pub order: Vec<String>,
+
+    /// Containers of the graph
+    pub nodes: Vec<AppDependencyGraphNode>,
+}
+
+#[derive(SimpleObject)]
+pub struct AppDependencyGraphNode {
+    /// Name of the container
+    pub name: String,
+
+    /// Dependency level, containers of level 0 have no dependency
+    pub level: usize,
+
+    /// Containers this one depends on
+    pub depends_on: Vec<String>,
+
+    /// Containers depending on this one
+    pub dependents: Vec<String>,
+}
+
+impl AppDependencyGraph {
+    pub fn new(containers: &[AppContainer]) -> Result<Self> {
+        let levels = compute_dependency_levels(containers)?;
+
+        let nodes = levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, containers_in_level)| {
+                containers_in_level
+                    .iter()
+                    .map(move |container| AppDependencyGraphNode {
+                        name: container.name.clone(),
+                        level,
+                        depends_on: container.depends_on.clone(),
+                        dependents: containers
+                            .iter()
+                            .filter(|other| other.depends_on.contains(&container.name))
+                            .map(|other| other.name.clone())
+                            .collect(),
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            order: nodes.iter().map(|node| node.name.clone()).collect(),
+            nodes,
+        })
+    }
+}
@@ -0,0 +1,279 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use async_graphql::SimpleObject;
+use serde_yaml::Value;
+
+use super::{ContainerEnvironmentVar, NAME_VALIDATOR};
+
+/// Environment variables parsed from a '.env' file
+#[derive(SimpleObject)]
+pub struct ParsedEnvFile {
+    pub env_vars: Vec<ContainerEnvironmentVar>,
+
+    /// Lines that could not be parsed
+    pub rejected_lines: Vec<RejectedEnvLine>,
+}
+
+#[derive(SimpleObject)]
+pub struct RejectedEnvLine {
+    /// Line number, starting at 1
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+/// Parse the content of a '.env' file
+///
+/// Supports comments, 'export' prefixes, single-quoted (literal) and double-quoted (with escapes)
+/// values, which can both span multiple lines. When a variable is defined multiple times,
+/// the last definition wins.
+pub fn parse_env_file(content: &str) -> ParsedEnvFile {
+    let lines = content.lines().collect::<Vec<_>>();
+
+    let mut env_vars = Vec::<ContainerEnvironmentVar>::new();
+    let mut rejected_lines = vec![];
+
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line_number = i + 1;
+        let line = lines[i];
+
+        i += 1;
+
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        match parse_assignment(trimmed, &lines, &mut i) {
+            Ok((name, value)) => {
+                env_vars.retain(|var| var.name != name);
+
+                env_vars.push(ContainerEnvironmentVar {
+                    name,
+                    value,
+                    secret: None,
+                });
+            }
+
+            Err(err) => rejected_lines.push(RejectedEnvLine {
+                line: line_number,
+                content: line.to_string(),
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    ParsedEnvFile {
+        env_vars,
+        rejected_lines,
+    }
+}
+
+/// Parse a 'NAME=value' line, consuming the following lines for multiline values
+fn parse_assignment(line: &str, lines: &[&str], next_line: &mut usize) -> Result<(String, String)> {
+    let line = match line.strip_prefix("export") {
+        Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+        _ => line,
+    };
+
+    let (name, value) = line
+        .split_once('=')
+        .context("Expected a 'NAME=value' assignment")?;
+
+    let name = name.trim_end();
+
+    if !NAME_VALIDATOR.is_match(name) {
+        bail!(
+            "Invalid variable name '{name}', please follow regex: {}",
+            NAME_VALIDATOR.as_str()
+        );
+    }
+
+    let untrimmed = value;
+    let value = value.trim_start();
+
+    let value = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let mut raw = value[1..].to_string();
+
+            loop {
+                if let Some(end) = find_closing_quote(&raw, quote) {
+                    let rest = raw[end + 1..].trim();
+
+                    if !rest.is_empty() && !rest.starts_with('#') {
+                        bail!("Unexpected content after closing quote: '{rest}'");
+                    }
+
+                    raw.truncate(end);
+                    break;
+                }
+
+                let Some(line) = lines.get(*next_line) else {
+                    bail!("Quoted value is never closed");
+                };
+
+                raw.push('\n');
+                raw.push_str(line);
+
+                *next_line += 1;
+            }
+
+            if quote == '"' {
+                unescape(&raw)
+            } else {
+                raw
+            }
+        }
+
+        // Inline comments start with a '#' preceded by any whitespace
+        _ => {
+            let comment_start = untrimmed
+                .char_indices()
+                .find(|(i, c)| *c == '#' && untrimmed[..*i].ends_with(char::is_whitespace))
+                .map_or(untrimmed.len(), |(i, _)| i);
+
+            untrimmed[..comment_start].trim().to_string()
+        }
+    };
+
+    Ok((name.to_string(), value))
+}
+
+fn find_closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Load the '.env' files referenced by a template's containers, relative to the provided directory
+///
+/// Containers of a store's templates reference them with an 'env_file' field, which is removed
+/// once loaded as it is not part of the templates the API accepts. Works on the raw YAML template,
+/// as its parameters may not be substituted yet.
+/// Variables explicitly declared in the template take precedence over the ones from the file.
+///
+/// Returns warnings about the lines and variables that could not be loaded,
+/// including variables with an empty value, as containers don't support them.
+pub fn load_template_env_files(template: &mut Value, base_dir: &Path) -> Result<Vec<String>> {
+    let mut warnings = vec![];
+
+    let Some(containers) = template.get_mut("containers") else {
+        return Ok(warnings);
+    };
+
+    let containers = containers
+        .as_sequence_mut()
+        .context("Template's 'containers' field is not a list")?;
+
+    for container in containers {
+        let container = container
+            .as_mapping_mut()
+            .context("Template's container is not a mapping")?;
+
+        let name = container
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("<unnamed>")
+            .to_string();
+
+        let env_file = match container.remove("env_file") {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(env_file)) => env_file,
+            Some(_) => bail!("Field 'env_file' of container '{name}' is not a string"),
+        };
+
+        let path = base_dir.join(&env_file);
+
+        let content = fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Failed to read environment file '{}' of container '{name}'",
+                path.display(),
+            )
+        })?;
+
+        let ParsedEnvFile {
+            env_vars,
+            rejected_lines,
+        } = parse_env_file(&content);
+
+        for rejected in rejected_lines {
+            warnings.push(format!(
+                "Ignored invalid line {} of environment file '{}': {}",
+                rejected.line,
+                path.display(),
+                rejected.reason
+            ));
+        }
+
+        let (env_vars, empty): (Vec<_>, Vec<_>) = env_vars
+            .into_iter()
+            .partition(|var| !var.value.trim().is_empty());
+
+        for var in empty {
+            warnings.push(format!(
+                "Ignored variable '{}' of environment file '{}' as it has an empty value",
+                var.name,
+                path.display()
+            ));
+        }
+
+        let declared = match container.remove("env_vars") {
+            None | Some(Value::Null) => vec![],
+            Some(Value::Sequence(declared)) => declared,
+            Some(_) => bail!("Field 'env_vars' of container '{name}' is not a list"),
+        };
+
+        let mut merged = env_vars
+            .into_iter()
+            .filter(|var| {
+                !declared
+                    .iter()
+                    .any(|other| other.get("name").and_then(Value::as_str) == Some(&var.name))
+            })
+            .map(serde_yaml::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to serialize environment variables")?;
+
+        merged.extend(declared);
+
+        container.insert(Value::from("env_vars"), Value::Sequence(merged));
+    }
+
+    Ok(warnings)
+}
@@ -0,0 +1,142 @@
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::Rng;
+
+static KEY_FILENAME: &str = "secrets.key";
+static SALT_FILENAME: &str = "secrets.salt";
+
+/// Prefix of encrypted values, used to tell them apart from plain ones
+static ENCRYPTED_PREFIX: &str = "onix-encrypted:";
+
+static NONCE_LENGTH: usize = 12;
+
+/// Value displayed by the API in place of secret values
+///
+/// When provided back as a secret's value, the existing value is kept.
+pub static REDACTED_VALUE: &str = "<redacted>";
+
+/// Key used to encrypt secret values at rest
+#[derive(Clone)]
+pub struct SecretsKey([u8; 32]);
+
+impl SecretsKey {
+    /// Load the key stored in the data directory, or generate it if it doesn't exist yet
+    pub fn load_or_generate(data_dir: &Path) -> Result<Self> {
+        let key_file = data_dir.join(KEY_FILENAME);
+
+        if key_file.exists() {
+            let key = fs::read(&key_file).context("Failed to read secrets key file")?;
+
+            let key = key
+                .try_into()
+                .map_err(|_| anyhow!("Secrets key file is corrupted (invalid length)"))?;
+
+            return Ok(Self(key));
+        }
+
+        let key = rand::thread_rng().gen::<[u8; 32]>();
+
+        write_private_file(&key_file, &key).context("Failed to save secrets key file")?;
+
+        Ok(Self(key))
+    }
+
+    /// Derive the key from a passphrase, using a salt stored in the data directory
+    pub fn from_passphrase(passphrase: &str, data_dir: &Path) -> Result<Self> {
+        if passphrase.is_empty() {
+            bail!("Please provide a non-empty passphrase");
+        }
+
+        let salt_file = data_dir.join(SALT_FILENAME);
+
+        let salt = if salt_file.exists() {
+            fs::read(&salt_file).context("Failed to read secrets salt file")?
+        } else {
+            let salt = rand::thread_rng().gen::<[u8; 16]>().to_vec();
+            write_private_file(&salt_file, &salt).context("Failed to save secrets salt file")?;
+            salt
+        };
+
+        let mut key = [0; 32];
+
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| anyhow!("Failed to derive secrets key from passphrase: {err}"))?;
+
+        Ok(Self(key))
+    }
+
+    pub fn encrypt(&self, value: &str) -> Result<String> {
+        let nonce = rand::thread_rng().gen::<[u8; NONCE_LENGTH]>();
+
+        let encrypted = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), value.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt secret value"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(encrypted);
+
+        Ok(format!("{ENCRYPTED_PREFIX}{}", BASE64.encode(payload)))
+    }
+
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let payload = value
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .context("Value is not encrypted")?;
+
+        let payload = BASE64
+            .decode(payload)
+            .context("Encrypted value is not valid base64")?;
+
+        if payload.len() < NONCE_LENGTH {
+            bail!("Encrypted value is too short");
+        }
+
+        let (nonce, encrypted) = payload.split_at(NONCE_LENGTH);
+
+        let decrypted = self
+            .cipher()
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| {
+                anyhow!("Failed to decrypt secret value (was it encrypted with another key or passphrase?)")
+            })?;
+
+        String::from_utf8(decrypted).context("Decrypted value contains invalid UTF-8 characters")
+    }
+
+    /// Decrypt a value if it is encrypted, or return it as is otherwise
+    pub fn reveal(&self, value: &str) -> Result<String> {
+        if is_encrypted(value) {
+            self.decrypt(value)
+        } else {
+            Ok(value.to_string())
+        }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+fn write_private_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create parent directory")?;
+    }
+
+    fs::write(path, content)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+    Ok(())
+}
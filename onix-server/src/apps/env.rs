@@ -1,25 +1,36 @@
 use std::path::PathBuf;
 
-use super::{app::AppIdentity, containers::AppContainerIdentity};
+use super::{app::AppIdentity, containers::AppContainerIdentity, encryption::SecretsKey};
 
 pub struct AppRunnerConfig {
     pub data_dir: PathBuf,
+    pub secrets_key: SecretsKey,
 }
 
+#[derive(Clone)]
 pub struct AppRunnerEnvironment {
     pub(super) apps_dir: PathBuf,
+    pub(super) secrets_key: SecretsKey,
 }
 
 impl AppRunnerEnvironment {
     pub fn new(config: AppRunnerConfig) -> Self {
         #[deny(unused_variables)]
-        let AppRunnerConfig { data_dir } = config;
+        let AppRunnerConfig {
+            data_dir,
+            secrets_key,
+        } = config;
 
         Self {
             apps_dir: data_dir.join("apps"),
+            secrets_key,
         }
     }
 
+    pub fn secrets_key(&self) -> &SecretsKey {
+        &self.secrets_key
+    }
+
     pub fn app_dir(&self, app: &AppIdentity) -> PathBuf {
         self.apps_dir.join(format!("{}-{}", app.name, app.id))
     }
@@ -28,6 +39,14 @@ impl AppRunnerEnvironment {
         self.app_dir(app).join("containers")
     }
 
+    pub fn app_shared_volumes_dir(&self, app: &AppIdentity) -> PathBuf {
+        self.app_dir(app).join("shared-volumes")
+    }
+
+    pub fn app_shared_volume_dir(&self, app: &AppIdentity, volume_name: &str) -> PathBuf {
+        self.app_shared_volumes_dir(app).join(volume_name)
+    }
+
     pub fn app_container_dir(&self, container: &AppContainerIdentity) -> PathBuf {
         self.app_containers_dir(&container.app)
             .join(format!("{}-{}", container.name, container.id))
@@ -1,9 +1,10 @@
 use anyhow::{Context, Result};
 use async_graphql::SimpleObject;
+use bollard::Docker;
 
 use crate::docker::{
-    ExistingContainer, ExistingContainerStatus, APP_ID_LABEL, APP_NAME_LABEL, CONTAINER_ID_LABEL,
-    CONTAINER_NAME_LABEL, NAME_PREFIX,
+    self, ExistingContainer, ExistingContainerStatus, APP_ID_LABEL, APP_NAME_LABEL,
+    CONTAINER_ID_LABEL, CONTAINER_NAME_LABEL, NAME_PREFIX,
 };
 
 use super::{AppContainerId, AppId};
@@ -78,3 +79,15 @@ impl ExistingAppContainer {
         }))
     }
 }
+
+/// List the existing Docker containers of all applications
+pub async fn list_existing_app_containers(docker: &Docker) -> Result<Vec<ExistingAppContainer>> {
+    let containers = docker::list_containers(docker)
+        .await
+        .context("Failed to obtain the list of existing Docker containers")?;
+
+    containers
+        .into_iter()
+        .filter_map(|container| ExistingAppContainer::decode(container).transpose())
+        .collect()
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use super::{App, AppVolumeType, NAME_VALIDATOR};
+
+/// Named path of the host, which templates can mount without knowing the actual path
+///
+/// e.g. 'media' => '/srv/media'
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone)]
+#[graphql(input_name_suffix = "Input")]
+pub struct GlobalPath {
+    pub id: String,
+
+    /// Absolute path on the Docker host
+    pub host_path: String,
+
+    pub description: Option<String>,
+}
+
+impl GlobalPath {
+    pub fn validate(&self) -> Result<()> {
+        #[deny(unused_variables)]
+        let Self {
+            id,
+            host_path,
+            description: _,
+        } = self;
+
+        if !NAME_VALIDATOR.is_match(id) {
+            bail!(
+                "Invalid global path ID '{id}', please follow regex: {}",
+                NAME_VALIDATOR.as_str()
+            );
+        }
+
+        if !Path::new(host_path).is_absolute() {
+            bail!("Host path of global path '{id}' must be absolute");
+        }
+
+        Ok(())
+    }
+}
+
+/// Applications using a global path
+#[derive(SimpleObject)]
+pub struct GlobalPathUsage {
+    pub global_path: GlobalPath,
+    pub apps: Vec<App>,
+}
+
+/// Find a global path in the registry from its identifier
+pub fn find_global_path<'a>(global_paths: &'a [GlobalPath], id: &str) -> Option<&'a GlobalPath> {
+    global_paths.iter().find(|path| path.id == id)
+}
+
+impl App {
+    /// List the identifiers of the global paths mounted by the application's containers
+    pub fn used_global_paths(&self) -> impl Iterator<Item = &str> {
+        self.containers
+            .iter()
+            .flat_map(|container| &container.volumes)
+            .filter_map(|volume| match &volume.variant {
+                AppVolumeType::GlobalPath { global_path_id, .. } => Some(global_path_id.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Ensure all global paths mounted by the application's containers are registered
+    pub fn check_global_paths(&self, global_paths: &[GlobalPath]) -> Result<()> {
+        for container in &self.containers {
+            for volume in &container.volumes {
+                if let AppVolumeType::GlobalPath { global_path_id, .. } = &volume.variant {
+                    if find_global_path(global_paths, global_path_id).is_none() {
+                        bail!(
+                            "Volume '{}' of container '{}' references undefined global path '{global_path_id}'",
+                            volume.name,
+                            container.name
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::cmp::Ordering;
+
+use async_graphql::{Enum, InputObject};
+use time::OffsetDateTime;
+
+use super::{App, AppRunningStatus};
+
+/// Criteria to filter the applications with, all provided criteria must match
+#[derive(InputObject, Default)]
+pub struct AppsFilter {
+    /// Only keep the applications with this tag
+    pub tag: Option<String>,
+
+    /// Only keep the applications of this group
+    pub group: Option<String>,
+
+    /// Only keep the applications whose name contains this string (case-insensitive)
+    pub name_contains: Option<String>,
+
+    /// Only keep the applications with this running status
+    pub status: Option<AppRunningStatus>,
+
+    /// Only keep the applications created after this date
+    pub created_after: Option<OffsetDateTime>,
+
+    /// Only keep the applications created before this date
+    pub created_before: Option<OffsetDateTime>,
+}
+
+impl AppsFilter {
+    /// Check if the provided application matches the filter
+    ///
+    /// The status must be provided when filtering by status, otherwise the application won't match.
+    pub fn matches(&self, app: &App, status: Option<AppRunningStatus>) -> bool {
+        #[deny(unused_variables)]
+        let Self {
+            tag,
+            group,
+            name_contains,
+            status: expected_status,
+            created_after,
+            created_before,
+        } = self;
+
+        if let Some(tag) = tag {
+            if !app.tags.contains(tag) {
+                return false;
+            }
+        }
+
+        if let Some(group) = group {
+            if app.group.as_ref() != Some(group) {
+                return false;
+            }
+        }
+
+        if let Some(name_contains) = name_contains {
+            if !app
+                .name
+                .to_lowercase()
+                .contains(&name_contains.to_lowercase())
+            {
+                return false;
+            }
+        }
+
+        if let Some(expected_status) = expected_status {
+            if status != Some(*expected_status) {
+                return false;
+            }
+        }
+
+        if let Some(created_after) = created_after {
+            if app.created_on <= *created_after {
+                return false;
+            }
+        }
+
+        if let Some(created_before) = created_before {
+            if app.created_on >= *created_before {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(InputObject)]
+pub struct AppsSort {
+    pub by: AppsSortField,
+    pub descending: Option<bool>,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum AppsSortField {
+    Name,
+
+    /// Applications without a group come last
+    Group,
+
+    CreatedOn,
+}
+
+impl AppsSort {
+    pub fn sort(&self, apps: &mut [App]) {
+        apps.sort_by(|a, b| {
+            let ordering = match self.by {
+                AppsSortField::Name => a.name.cmp(&b.name),
+
+                AppsSortField::Group => match (&a.group, &b.group) {
+                    (Some(a_group), Some(b_group)) => a_group.cmp(b_group),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+                .then_with(|| a.name.cmp(&b.name)),
+
+                AppsSortField::CreatedOn => a.created_on.cmp(&b.created_on),
+            };
+
+            if self.descending == Some(true) {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+impl Default for AppsSort {
+    fn default() -> Self {
+        Self {
+            by: AppsSortField::Name,
+            descending: None,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{bail, Context, Result};
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use super::AppContainerTemplate;
+
+/// Maximum size of an icon embedded as a data URL
+static MAX_EMBEDDED_ICON_SIZE: usize = 256 * 1024;
+
+/// Informations displayed about an application
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppMetadata {
+    pub description: Option<String>,
+
+    /// URL of the icon, or icon embedded as a data URL (e.g. 'data:image/png;base64,...')
+    pub icon: Option<String>,
+
+    /// URL of the application's homepage
+    pub homepage: Option<String>,
+
+    /// URL of the application's documentation
+    pub documentation: Option<String>,
+
+    pub category: Option<String>,
+
+    /// Container port serving the application's web interface
+    pub web_ui: Option<AppWebUi>,
+}
+
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppWebUi {
+    /// Name of the container serving the web interface
+    pub container: String,
+
+    /// Port of the container the web interface listens on, must be bound to a host port
+    pub port: u16,
+
+    /// Path of the web interface, e.g. '/admin'
+    pub path: Option<String>,
+
+    /// Serve the web interface over HTTPS
+    pub https: Option<bool>,
+}
+
+impl AppMetadata {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self, containers: &[AppContainerTemplate]) -> Result<()> {
+        #[deny(unused_variables)]
+        let Self {
+            description: _,
+            icon,
+            homepage,
+            documentation,
+            category,
+            web_ui,
+        } = self;
+
+        if let Some(icon) = icon {
+            if icon.starts_with("data:") {
+                if !icon.starts_with("data:image/") {
+                    bail!("Embedded icon must be an image");
+                }
+
+                if icon.len() > MAX_EMBEDDED_ICON_SIZE {
+                    bail!(
+                        "Embedded icon is too large (maximum is {} KiB)",
+                        MAX_EMBEDDED_ICON_SIZE / 1024
+                    );
+                }
+            } else {
+                validate_url(icon).context("Invalid icon URL")?;
+            }
+        }
+
+        if let Some(homepage) = homepage {
+            validate_url(homepage).context("Invalid homepage URL")?;
+        }
+
+        if let Some(documentation) = documentation {
+            validate_url(documentation).context("Invalid documentation URL")?;
+        }
+
+        if category
+            .as_ref()
+            .is_some_and(|category| category.trim().is_empty())
+        {
+            bail!("Please provide a non-empty category");
+        }
+
+        if let Some(web_ui) = web_ui {
+            web_ui.validate(containers)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AppWebUi {
+    fn validate(&self, containers: &[AppContainerTemplate]) -> Result<()> {
+        #[deny(unused_variables)]
+        let Self {
+            container,
+            port,
+            path,
+            https: _,
+        } = self;
+
+        let target = containers
+            .iter()
+            .find(|c| &c.name == container)
+            .with_context(|| format!("Web UI references unknown container '{container}'"))?;
+
+        if !target.port_bindings.iter().any(|binding| {
+            binding.container_port.port == *port && binding.container_port.port_type.covers_tcp()
+        }) {
+            bail!(
+                "Web UI references TCP port {port} of container '{container}', which is not bound"
+            );
+        }
+
+        if path.as_ref().is_some_and(|path| !path.starts_with('/')) {
+            bail!("Web UI path must start with a '/'");
+        }
+
+        Ok(())
+    }
+
+    /// Build the URL of the web interface from the address the host's ports are reachable at
+    pub fn url(&self, address: &str, host_port: u16) -> String {
+        let address = if address.contains(':') {
+            format!("[{address}]")
+        } else {
+            address.to_string()
+        };
+
+        format!(
+            "{}://{address}:{host_port}{}",
+            if self.https == Some(true) {
+                "https"
+            } else {
+                "http"
+            },
+            self.path.as_deref().unwrap_or("/")
+        )
+    }
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        bail!("URL '{url}' must start with 'http://' or 'https://'");
+    }
+
+    Ok(())
+}
@@ -1,16 +1,42 @@
 mod app;
+mod app_deps;
 mod containers;
+mod deps;
+mod dotenv;
+mod encryption;
 mod env;
 mod existing_containers;
+mod global_paths;
+mod listing;
+mod metadata;
+mod origin;
+mod params;
+mod references;
 mod runner;
+mod secrets;
 mod templates;
+mod volumes;
 
-pub use app::{App, AppId};
+pub use app::{App, AppDesiredState, AppId, AppUpdateDiff};
+pub use app_deps::{
+    check_app_dependencies, check_desired_states, sorted_app_dependencies, sorted_app_dependents,
+    AppDependency,
+};
 pub use containers::{AppContainer, AppContainerId};
+pub use deps::{AppDependencyGraph, AppDependencyGraphNode};
+pub use dotenv::{load_template_env_files, parse_env_file, ParsedEnvFile, RejectedEnvLine};
+pub use encryption::{SecretsKey, REDACTED_VALUE};
 pub use env::{AppRunnerConfig, AppRunnerEnvironment};
-pub use existing_containers::ExistingAppContainer;
-pub use runner::{AppRunner, AppRunningStatus};
+pub use existing_containers::{list_existing_app_containers, ExistingAppContainer};
+pub use global_paths::{find_global_path, GlobalPath, GlobalPathUsage};
+pub use listing::{AppsFilter, AppsSort, AppsSortField};
+pub use metadata::{AppMetadata, AppWebUi};
+pub use origin::{parse_template_version, AppTemplateOrigin, AppTemplateSource, OutdatedApp};
+pub use params::*;
+pub use runner::{AppRunner, AppRunningStatus, ContainerActionReport};
+pub use secrets::{AppSecret, AppTemplateSecret, SecretFormat};
 pub use templates::*;
+pub use volumes::{AppVolumeInfo, AppVolumeStorage};
 
 use once_cell::sync::Lazy;
 use pomsky_macro::pomsky;
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+use super::{AppId, AppTemplateParamValue, ContainerEnvironmentVar, NAME_VALIDATOR};
+
+/// Template an application was created from
+#[derive(SimpleObject, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppTemplateOrigin {
+    /// URL of the store the template comes from
+    pub store_url: Option<String>,
+
+    /// Identifier of the template
+    pub template_id: String,
+
+    /// Version of the template (semver)
+    pub version: String,
+
+    /// Values provided for the template's parameters, secret ones being encrypted
+    #[graphql(skip)]
+    #[serde(default)]
+    pub params: Vec<AppTemplateParamValue>,
+
+    /// Environment variables of each container, as they were when installing the template
+    ///
+    /// Used to detect the values edited by the user, which must be kept when upgrading
+    #[graphql(skip)]
+    #[serde(default)]
+    pub installed_env_vars: BTreeMap<String, Vec<ContainerEnvironmentVar>>,
+}
+
+/// Where an application's template comes from
+pub struct AppTemplateSource {
+    pub store_url: Option<String>,
+
+    /// Values provided for the template's parameters, secret ones being encrypted
+    pub params: Vec<AppTemplateParamValue>,
+}
+
+/// Application with a newer template available
+#[derive(SimpleObject)]
+pub struct OutdatedApp {
+    pub app_id: AppId,
+    pub app_name: String,
+    pub template_id: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+pub fn parse_template_version(version: &str) -> Result<semver::Version> {
+    semver::Version::parse(version)
+        .with_context(|| format!("Invalid template version '{version}', expected semver"))
+}
+
+/// Build the origin of an application from its template's identifier and version
+pub fn template_origin(
+    id: Option<String>,
+    version: Option<String>,
+    source: Option<AppTemplateSource>,
+) -> Result<Option<AppTemplateOrigin>> {
+    let (template_id, version) = match (id, version) {
+        (Some(id), Some(version)) => (id, version),
+        (None, None) => {
+            if source
+                .as_ref()
+                .is_some_and(|source| source.store_url.is_some())
+            {
+                bail!("Templates from stores must have an ID and a version");
+            }
+
+            return Ok(None);
+        }
+        (Some(_), None) => bail!("Please provide a version for the template"),
+        (None, Some(_)) => bail!("Please provide an ID for the template"),
+    };
+
+    if !NAME_VALIDATOR.is_match(&template_id) {
+        bail!(
+            "Invalid template ID '{template_id}', please follow regex: {}",
+            NAME_VALIDATOR.as_str()
+        );
+    }
+
+    parse_template_version(&version)?;
+
+    let (store_url, params) = match source {
+        Some(AppTemplateSource { store_url, params }) => (store_url, params),
+        None => (None, vec![]),
+    };
+
+    Ok(Some(AppTemplateOrigin {
+        store_url,
+        template_id,
+        version,
+        params,
+        installed_env_vars: BTreeMap::new(),
+    }))
+}
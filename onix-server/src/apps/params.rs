@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::{Enum, InputObject, SimpleObject};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use super::{encryption::SecretsKey, AppTemplate, NAME_VALIDATOR};
+
+/// Reference to a template parameter, e.g. '${params.db_password}'
+static PARAM_REF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{params\.([a-zA-Z0-9_-]+)\}").unwrap());
+
+/// Parameter declared by a template, to be provided by the user when creating an application from it
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppTemplateParam {
+    pub name: String,
+    pub description: Option<String>,
+    pub param_type: AppTemplateParamType,
+
+    /// Value to use when none is provided
+    pub default: Option<String>,
+
+    /// Regex the value must match (for strings, secrets and paths)
+    pub pattern: Option<String>,
+}
+
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AppTemplateParamType {
+    String,
+    Secret,
+    Port,
+    Path,
+    Boolean,
+}
+
+/// Value provided for a template parameter
+#[derive(InputObject, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AppTemplateParamValue {
+    pub name: String,
+    pub value: String,
+}
+
+impl AppTemplateParam {
+    pub fn validate_value(&self, value: &str) -> Result<()> {
+        match self.param_type {
+            AppTemplateParamType::String => {}
+
+            AppTemplateParamType::Secret => {
+                if value.is_empty() {
+                    bail!("Please provide a non-empty secret");
+                }
+            }
+
+            AppTemplateParamType::Port => {
+                let port = value
+                    .parse::<u16>()
+                    .with_context(|| format!("Invalid port number '{value}'"))?;
+
+                if port == 0 {
+                    bail!("Port number cannot be zero");
+                }
+            }
+
+            AppTemplateParamType::Path => {
+                if !value.starts_with('/') {
+                    bail!("Path '{value}' must be absolute");
+                }
+            }
+
+            AppTemplateParamType::Boolean => {
+                if value != "true" && value != "false" {
+                    bail!("Invalid boolean '{value}', expected 'true' or 'false'");
+                }
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("Invalid validation pattern '{pattern}'"))?;
+
+            if !regex.is_match(value) {
+                bail!("Value does not match pattern '{pattern}'");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert a (validated) value to its YAML representation
+    fn to_yaml_value(&self, value: &str) -> Value {
+        match self.param_type {
+            AppTemplateParamType::String
+            | AppTemplateParamType::Secret
+            | AppTemplateParamType::Path => Value::String(value.to_string()),
+
+            AppTemplateParamType::Port => Value::Number(
+                value
+                    .parse::<u16>()
+                    .expect("Assertion error: port parameter was not validated")
+                    .into(),
+            ),
+
+            AppTemplateParamType::Boolean => Value::Bool(value == "true"),
+        }
+    }
+}
+
+/// Get the parameters declared by a YAML template
+pub fn decode_template_params(template: &str) -> Result<Vec<AppTemplateParam>> {
+    let template =
+        serde_yaml::from_str::<Value>(template).context("Failed to parse YAML template")?;
+
+    let params = match template.get("params") {
+        Some(params) => serde_yaml::from_value::<Option<Vec<AppTemplateParam>>>(params.clone())
+            .context("Failed to decode the template's parameters")?
+            .unwrap_or_default(),
+        None => vec![],
+    };
+
+    for (i, param) in params.iter().enumerate() {
+        if !NAME_VALIDATOR.is_match(&param.name) {
+            bail!(
+                "Invalid parameter name '{}', please follow regex: {}",
+                param.name,
+                NAME_VALIDATOR.as_str()
+            );
+        }
+
+        if params[..i].iter().any(|other| other.name == param.name) {
+            bail!("Parameter '{}' was declared twice", param.name);
+        }
+
+        if let Some(default) = &param.default {
+            param
+                .validate_value(default)
+                .with_context(|| format!("Invalid default value for parameter '{}'", param.name))?;
+        }
+    }
+
+    Ok(params)
+}
+
+/// Substitute the parameters of a YAML template with the provided values
+pub fn resolve_template(template: &str, values: Vec<AppTemplateParamValue>) -> Result<AppTemplate> {
+    let params = decode_template_params(template)?;
+
+    for value in &values {
+        if !params.iter().any(|param| param.name == value.name) {
+            bail!("Template has no parameter named '{}'", value.name);
+        }
+    }
+
+    let mut resolved = HashMap::new();
+
+    for param in &params {
+        let value = values
+            .iter()
+            .find(|value| value.name == param.name)
+            .map(|value| value.value.clone())
+            .or_else(|| param.default.clone())
+            .with_context(|| format!("Please provide a value for parameter '{}'", param.name))?;
+
+        param
+            .validate_value(&value)
+            .with_context(|| format!("Invalid value for parameter '{}'", param.name))?;
+
+        resolved.insert(param.name.as_str(), (param, value));
+    }
+
+    let mut template =
+        serde_yaml::from_str::<Value>(template).context("Failed to parse YAML template")?;
+
+    if let Value::Mapping(map) = &mut template {
+        map.remove("params");
+    }
+
+    substitute_template_params(&mut template, &resolved)?;
+
+    serde_yaml::from_value(template)
+        .context("Failed to decode the template after substituting its parameters")
+}
+
+/// Substitute the parameters of a (raw) template
+///
+/// Secret parameters can only be referenced in the value of environment variables,
+/// which are then marked as secret so they are encrypted at rest and redacted from the API.
+fn substitute_template_params(
+    template: &mut Value,
+    params: &HashMap<&str, (&AppTemplateParam, String)>,
+) -> Result<()> {
+    let containers = template
+        .get_mut("containers")
+        .and_then(Value::as_sequence_mut)
+        .map(std::mem::take);
+
+    substitute_public_params(template, params)?;
+
+    let Some(mut containers) = containers else {
+        return Ok(());
+    };
+
+    for container in &mut containers {
+        let env_vars = container
+            .get_mut("env_vars")
+            .and_then(Value::as_sequence_mut)
+            .map(std::mem::take);
+
+        substitute_public_params(container, params)?;
+
+        let Some(mut env_vars) = env_vars else {
+            continue;
+        };
+
+        for var in &mut env_vars {
+            let Some(var) = var.as_mapping_mut() else {
+                substitute_public_params(var, params)?;
+                continue;
+            };
+
+            let mut is_secret = false;
+
+            for (key, item) in var.iter_mut() {
+                if key.as_str() != Some("value") {
+                    substitute_public_params(item, params)?;
+                    continue;
+                }
+
+                // Secret values are used as is, so other references would not be resolved
+                if let Value::String(string) = item {
+                    if references_secret_param(string, params)
+                        && PARAM_REF.replace_all(string, "").contains("${")
+                    {
+                        bail!("Environment variables using secret parameters cannot contain other references");
+                    }
+                }
+
+                is_secret |= substitute_params(item, params)?;
+            }
+
+            if is_secret {
+                var.insert(Value::from("secret"), Value::Bool(true));
+            }
+        }
+
+        container["env_vars"] = Value::Sequence(env_vars);
+    }
+
+    template["containers"] = Value::Sequence(containers);
+
+    Ok(())
+}
+
+/// Substitute parameters in a part of a template which can't hold secret values
+fn substitute_public_params(
+    value: &mut Value,
+    params: &HashMap<&str, (&AppTemplateParam, String)>,
+) -> Result<()> {
+    if substitute_params(value, params)? {
+        bail!("Secret parameters can only be referenced in the value of environment variables");
+    }
+
+    Ok(())
+}
+
+fn references_secret_param(
+    value: &str,
+    params: &HashMap<&str, (&AppTemplateParam, String)>,
+) -> bool {
+    PARAM_REF.captures_iter(value).any(|captures| {
+        params
+            .get(&captures[1])
+            .is_some_and(|(param, _)| param.param_type == AppTemplateParamType::Secret)
+    })
+}
+
+/// Substitute parameters in a value
+///
+/// Returns `true` if a secret parameter was substituted.
+fn substitute_params(
+    value: &mut Value,
+    params: &HashMap<&str, (&AppTemplateParam, String)>,
+) -> Result<bool> {
+    let mut has_secret = false;
+
+    match value {
+        Value::String(string) => {
+            let mut get_param = |name: &str| {
+                let (param, param_value) = params
+                    .get(name)
+                    .with_context(|| format!("Template references unknown parameter '{name}'"))?;
+
+                has_secret |= param.param_type == AppTemplateParamType::Secret;
+
+                Ok::<_, anyhow::Error>((*param, param_value))
+            };
+
+            // A value only made of a reference keeps the parameter's type (e.g. ports)
+            if let Some(captures) = PARAM_REF.captures(string) {
+                if captures[0].len() == string.len() {
+                    let (param, param_value) = get_param(&captures[1])?;
+                    *value = param.to_yaml_value(param_value);
+                    return Ok(has_secret);
+                }
+            }
+
+            let mut substituted = String::with_capacity(string.len());
+            let mut last_end = 0;
+
+            for captures in PARAM_REF.captures_iter(string) {
+                let whole = captures.get(0).unwrap();
+                let (_, param_value) = get_param(&captures[1])?;
+
+                substituted.push_str(&string[last_end..whole.start()]);
+                substituted.push_str(param_value);
+
+                last_end = whole.end();
+            }
+
+            substituted.push_str(&string[last_end..]);
+
+            *string = substituted;
+        }
+
+        Value::Sequence(seq) => {
+            for item in seq {
+                has_secret |= substitute_params(item, params)?;
+            }
+        }
+
+        Value::Mapping(map) => {
+            for (_, item) in map.iter_mut() {
+                has_secret |= substitute_params(item, params)?;
+            }
+        }
+
+        Value::Tagged(tagged) => has_secret = substitute_params(&mut tagged.value, params)?,
+
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+
+    Ok(has_secret)
+}
+
+/// Encrypt the values provided for the secret parameters of a YAML template
+pub fn seal_param_values(
+    template: &str,
+    values: Vec<AppTemplateParamValue>,
+    key: &SecretsKey,
+) -> Result<Vec<AppTemplateParamValue>> {
+    let params = decode_template_params(template)?;
+
+    values
+        .into_iter()
+        .map(|AppTemplateParamValue { name, value }| {
+            let is_secret = params.iter().any(|param| {
+                param.name == name && param.param_type == AppTemplateParamType::Secret
+            });
+
+            Ok(AppTemplateParamValue {
+                value: if is_secret {
+                    key.encrypt(&value)?
+                } else {
+                    value
+                },
+                name,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Reference to the application or one of its containers, e.g. '${containers.db.hostname}'
+static REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{((?:app|containers)\.[a-zA-Z0-9_.-]+)\}").unwrap());
+
+/// Reference in an environment variable's value, resolved when the container is created
+pub enum EnvReference<'a> {
+    /// '${app.id}'
+    AppId,
+
+    /// '${app.name}'
+    AppName,
+
+    /// '${containers.<name>.hostname}', reachable from the application's other containers
+    ContainerHostname(&'a str),
+
+    /// '${containers.<name>.port.<container port>}', resolves to the container port
+    /// which the application's other containers can reach
+    ContainerPort(&'a str, u16),
+
+    /// '${containers.<name>.host_port.<container port>}', resolves to the host port it is bound to
+    ContainerHostPort(&'a str, u16),
+}
+
+impl<'a> EnvReference<'a> {
+    fn parse(path: &'a str) -> Result<Self> {
+        match path.split('.').collect::<Vec<_>>().as_slice() {
+            ["app", "id"] => Ok(Self::AppId),
+            ["app", "name"] => Ok(Self::AppName),
+            ["containers", name, "hostname"] => Ok(Self::ContainerHostname(name)),
+            ["containers", name, "port", port] => {
+                Ok(Self::ContainerPort(name, parse_port(path, port)?))
+            }
+            ["containers", name, "host_port", port] => {
+                Ok(Self::ContainerHostPort(name, parse_port(path, port)?))
+            }
+            _ => bail!("Unknown reference '${{{path}}}'"),
+        }
+    }
+}
+
+fn parse_port(path: &str, port: &str) -> Result<u16> {
+    port.parse()
+        .with_context(|| format!("Invalid port number '{port}' in '${{{path}}}'"))
+}
+
+/// List the references made in a value
+pub fn parse_references(value: &str) -> Result<Vec<EnvReference>> {
+    REFERENCE
+        .captures_iter(value)
+        .map(|captures| EnvReference::parse(captures.get(1).unwrap().as_str()))
+        .collect()
+}
+
+/// Replace references in a value using the provided resolver
+pub fn resolve_references(
+    value: &str,
+    resolver: impl Fn(&EnvReference) -> Result<String>,
+) -> Result<String> {
+    let mut resolved = String::with_capacity(value.len());
+    let mut last_end = 0;
+
+    for captures in REFERENCE.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let reference = EnvReference::parse(captures.get(1).unwrap().as_str())?;
+
+        resolved.push_str(&value[last_end..whole.start()]);
+        resolved.push_str(&resolver(&reference)?);
+
+        last_end = whole.end();
+    }
+
+    resolved.push_str(&value[last_end..]);
+
+    Ok(resolved)
+}
+
+/// Update the references to a renamed container in a value
+pub fn rename_container_references(value: &str, name: &str, new_name: &str) -> String {
+    value.replace(
+        &format!("${{containers.{name}."),
+        &format!("${{containers.{new_name}."),
+    )
+}
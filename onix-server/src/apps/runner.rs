@@ -1,38 +1,66 @@
 use std::{
-    cmp::Ordering,
     collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
     sync::Arc,
 };
 
 use anyhow::{bail, Context, Result};
-use async_graphql::Enum;
+use async_graphql::{Enum, SimpleObject};
 use bollard::Docker;
 use futures::future::try_join_all;
-use log::info;
+use log::{error, info};
 
 use crate::{
-    apps::AppVolumeType,
+    apps::{AppSharedVolumeType, AppVolumeStorage, AppVolumeType, VolumeOwnership},
+    data::UserData,
     docker::{
-        self, ContainerCreationConfig, ContainerMount, ContainerRestartPolicy,
-        ExistingContainerStatus, APP_ID_LABEL, APP_NAME_LABEL, CONTAINER_ID_LABEL,
-        CONTAINER_NAME_LABEL,
+        self, ContainerCreationConfig, ContainerEnvironmentVar, ContainerMount, ContainerNetwork,
+        ContainerNetworkMode, ContainerRestartPolicy, ExistingContainerStatus, APP_ID_LABEL,
+        APP_NAME_LABEL, CONTAINER_ID_LABEL, CONTAINER_NAME_LABEL,
     },
+    utils::fs::{apply_ownership, copy_dir},
 };
 
 use super::{
-    app::App, containers::AppContainer, env::AppRunnerEnvironment,
-    existing_containers::ExistingAppContainer, AppTemplate,
+    app::{App, AppUpdateDiff},
+    containers::AppContainer,
+    deps::{transitive_dependencies, transitive_dependents},
+    env::AppRunnerEnvironment,
+    existing_containers::ExistingAppContainer,
+    global_paths::find_global_path,
+    AppTemplate,
 };
 
 pub struct AppRunner<'a, 'b, 'c> {
     docker: &'a Docker,
     env: &'b AppRunnerEnvironment,
+    user_data: &'b UserData,
     app: &'c App,
 }
 
 impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
-    pub fn new(docker: &'a Docker, env: &'b AppRunnerEnvironment, app: &'c App) -> Self {
-        Self { docker, env, app }
+    pub fn new(
+        docker: &'a Docker,
+        env: &'b AppRunnerEnvironment,
+        user_data: &'b UserData,
+        app: &'c App,
+    ) -> Self {
+        Self {
+            docker,
+            env,
+            user_data,
+            app,
+        }
+    }
+
+    /// Create a runner for another application, using the same Docker host and environment
+    pub fn for_app<'d>(&self, app: &'d App) -> AppRunner<'a, 'b, 'd> {
+        AppRunner::new(self.docker, self.env, self.user_data, app)
+    }
+
+    pub fn app(&self) -> &'c App {
+        self.app
     }
 
     async fn list_existing_containers(&self) -> Result<Vec<ExistingAppContainer>> {
@@ -47,6 +75,15 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
     }
 
     pub async fn status(&self) -> Result<AppRunningStatus> {
+        let existing = self.list_existing_containers().await?;
+
+        Ok(self.status_from(&existing))
+    }
+
+    /// Compute the application's status from a list of existing containers
+    ///
+    /// Allows to compute the status of multiple applications with a single listing of the Docker containers.
+    pub fn status_from(&self, existing: &[ExistingAppContainer]) -> AppRunningStatus {
         let container_ids = self
             .app
             .containers
@@ -54,10 +91,8 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             .map(|container| container.id)
             .collect::<HashSet<_>>();
 
-        let existing = self.list_existing_containers().await?;
-
         let existing = existing
-            .into_iter()
+            .iter()
             .filter(|existing| {
                 existing.app_id == self.app.id && container_ids.contains(&existing.container_id)
             })
@@ -76,11 +111,11 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             .count();
 
         if created_count == 0 {
-            return Ok(AppRunningStatus::NotCreated);
+            return AppRunningStatus::NotCreated;
         }
 
         if created_count < self.app.containers.len() {
-            return Ok(AppRunningStatus::PartiallyCreated);
+            return AppRunningStatus::PartiallyCreated;
         }
 
         let statuses = existing
@@ -89,25 +124,25 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             .collect::<Vec<_>>();
 
         if statuses.contains(&ExistingContainerStatus::Dead) {
-            return Ok(AppRunningStatus::Zombie);
+            return AppRunningStatus::Zombie;
         }
 
         if statuses.contains(&ExistingContainerStatus::Paused)
             || statuses.contains(&ExistingContainerStatus::Removing)
             || statuses.contains(&ExistingContainerStatus::Restarting)
         {
-            return Ok(AppRunningStatus::Intermediary);
+            return AppRunningStatus::Intermediary;
         }
 
         if statuses.iter().all(|status| {
             *status == ExistingContainerStatus::Created
                 || *status == ExistingContainerStatus::Exited
         }) {
-            return Ok(AppRunningStatus::Stopped);
+            return AppRunningStatus::Stopped;
         }
 
         if statuses.contains(&ExistingContainerStatus::Exited) {
-            return Ok(AppRunningStatus::PartiallyRunning);
+            return AppRunningStatus::PartiallyRunning;
         }
 
         assert!(
@@ -117,7 +152,7 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             "Assertion error: invalid predicates on existing container status"
         );
 
-        Ok(AppRunningStatus::FullyRunning)
+        AppRunningStatus::FullyRunning
     }
 
     pub async fn create_containers(&self) -> Result<()> {
@@ -142,7 +177,7 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             }
         }
 
-        let containers = self.sort_containers_by_deps();
+        let containers = self.sort_containers_by_deps()?;
 
         for (i, container) in containers.iter().enumerate() {
             info!(
@@ -153,16 +188,7 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
                 container.id
             );
 
-            let config = self.generate_container_config(container);
-
-            docker::create_container(self.docker, config)
-                .await
-                .with_context(|| {
-                    format!(
-                        "Failed to create container '{}' for app '{}'",
-                        container.name, container.app.name
-                    )
-                })?;
+            self.create_container(container).await?;
         }
 
         info!("> All containers were successfully created!");
@@ -170,6 +196,199 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
         Ok(())
     }
 
+    /// Create the application's containers which don't exist yet
+    ///
+    /// Allows to complete a partially created application.
+    pub async fn create_missing_containers(&self) -> Result<()> {
+        let existing = self.list_existing_containers().await?;
+
+        for container in self.sort_containers_by_deps()? {
+            if existing.iter().any(|c| c.container_id == container.id) {
+                continue;
+            }
+
+            info!("> Creating missing container '{}'...", container.name);
+
+            self.create_container(container).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_container(&self, container: &AppContainer) -> Result<()> {
+        self.prepare_external_volumes(container)?;
+
+        let config = self.generate_container_config(container)?;
+
+        docker::create_container(self.docker, config)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create container '{}' for app '{}'",
+                    container.name, container.app.name
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Apply an update to the application's Docker containers
+    ///
+    /// Only the containers that were added, removed or changed are touched.
+    /// If the application was running, the (re-)created containers are started.
+    pub async fn apply_update(&self, updated: &App, diff: &AppUpdateDiff) -> Result<()> {
+        assert_eq!(
+            self.app.id, updated.id,
+            "Assertion error: tried to apply an update from another application in runner"
+        );
+
+        let was_running = match self.status().await? {
+            AppRunningStatus::NotCreated => return Ok(()),
+            AppRunningStatus::Zombie => bail!("At least one container is in zombie mode"),
+            AppRunningStatus::Intermediary => {
+                bail!("At least one container is in an intermediary state")
+            }
+            // Allows to retry an update which failed halfway
+            AppRunningStatus::PartiallyCreated | AppRunningStatus::Stopped => false,
+            AppRunningStatus::PartiallyRunning | AppRunningStatus::FullyRunning => true,
+        };
+
+        info!(
+            "Updating containers for application '{}' [{}]...",
+            self.app.name, self.app.id
+        );
+
+        for container in self.sort_containers_by_deps()?.into_iter().rev() {
+            if !diff.must_remove(&container.name) {
+                continue;
+            }
+
+            let Some(infos) = self.get_container_infos(container).await? else {
+                continue;
+            };
+
+            if infos.status == ExistingContainerStatus::Running {
+                info!("> Stopping container '{}'...", container.name);
+
+                docker::stop_container(
+                    self.docker,
+                    &infos.docker_container_id,
+                    container.stop_timeout,
+                )
+                .await?;
+            }
+
+            info!("> Removing container '{}'...", container.name);
+
+            docker::remove_container(self.docker, &infos.docker_container_id).await?;
+        }
+
+        let updated_runner = self.for_app(updated);
+
+        for container in updated_runner.sort_containers_by_deps()? {
+            if !diff.must_create(&container.name)
+                || updated_runner
+                    .get_container_infos(container)
+                    .await?
+                    .is_some()
+            {
+                continue;
+            }
+
+            info!("> Creating container '{}'...", container.name);
+
+            updated_runner.create_container(container).await?;
+
+            if was_running {
+                info!("> Starting container '{}'...", container.name);
+
+                docker::start_container(self.docker, &container.docker_container_name())
+                    .await
+                    .with_context(|| format!("Failed to start container '{}'", container.name))?;
+            }
+        }
+
+        info!("> Application was successfully updated!");
+
+        Ok(())
+    }
+
+    /// Apply a renaming of the application or of some of its containers
+    ///
+    /// Data directories are moved, and existing containers are re-created to update their labels.
+    pub async fn apply_rename(&self, renamed: &App, diff: &AppUpdateDiff) -> Result<()> {
+        let mut moves = vec![];
+
+        if renamed.name != self.app.name {
+            moves.push((
+                self.env.app_dir(&self.app.identity()),
+                self.env.app_dir(&renamed.identity()),
+            ));
+        }
+
+        for container in &renamed.containers {
+            let Some(previous) = self.app.containers.iter().find(|c| c.id == container.id) else {
+                continue;
+            };
+
+            if previous.name != container.name {
+                // The application's directory has already been moved at this point
+                let mut previous = previous.clone();
+                previous.app = renamed.identity();
+
+                moves.push((
+                    self.env.app_container_dir(&previous.identity()),
+                    self.env.app_container_dir(&container.identity()),
+                ));
+            }
+        }
+
+        for (from, to) in &moves {
+            if !from.exists() {
+                continue;
+            }
+
+            if to.exists() {
+                bail!(
+                    "Cannot move data directory to '{}' as it already exists",
+                    to.display()
+                );
+            }
+
+            info!(
+                "> Moving data directory '{}' to '{}'...",
+                from.display(),
+                to.display()
+            );
+
+            fs::rename(from, to).with_context(|| {
+                format!(
+                    "Failed to move data directory '{}' to '{}'",
+                    from.display(),
+                    to.display()
+                )
+            })?;
+        }
+
+        if let Err(err) = self.apply_update(renamed, diff).await {
+            for (from, to) in moves.iter().rev() {
+                if to.exists() {
+                    if let Err(err) = fs::rename(to, from) {
+                        error!(
+                            "Failed to move data directory '{}' back to '{}': {err}",
+                            to.display(),
+                            from.display()
+                        );
+                    }
+                }
+            }
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
     pub async fn get_container_infos(
         &self,
         container: &AppContainer,
@@ -199,7 +418,7 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             AppRunningStatus::FullyRunning => return Ok(()),
         }
 
-        let containers = self.sort_containers_by_deps();
+        let containers = self.sort_containers_by_deps()?;
 
         for (i, container) in containers.iter().enumerate() {
             info!(
@@ -220,38 +439,184 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
         Ok(())
     }
 
+    /// Stop the application's containers
+    ///
+    /// Containers are stopped in reverse dependency order, dependents being stopped before their dependencies.
+    /// Containers of a same dependency level are stopped in parallel.
     pub async fn stop(&self) -> Result<()> {
-        let containers = self.list_existing_containers().await?;
+        let levels = self.existing_containers_by_reverse_deps().await?;
 
         let docker = Arc::new(self.docker.clone());
 
-        let tasks = containers
-            .into_iter()
-            .filter(|container| container.app_id == self.app.id)
-            .map(|container| {
-                let docker = Arc::clone(&docker);
-
-                async move {
-                    docker::stop_container(&docker, &container.docker_container_id)
+        for level in levels {
+            let tasks = level
+                .into_iter()
+                .filter(|(_, existing)| {
+                    matches!(
+                        existing.status,
+                        ExistingContainerStatus::Running
+                            | ExistingContainerStatus::Restarting
+                            | ExistingContainerStatus::Paused
+                    )
+                })
+                .map(|(container, existing)| {
+                    let docker = Arc::clone(&docker);
+
+                    async move {
+                        info!("> Stopping container '{}'...", existing.container_name);
+
+                        docker::stop_container(
+                            &docker,
+                            &existing.docker_container_id,
+                            container.and_then(|container| container.stop_timeout),
+                        )
                         .await
                         .with_context(|| {
-                            format!("Failed to stop container '{}'", container.container_name)
+                            format!("Failed to stop container '{}'", existing.container_name)
                         })?;
 
-                    Ok::<(), anyhow::Error>(())
-                }
-            })
-            .collect::<Vec<_>>();
+                        Ok::<(), anyhow::Error>(())
+                    }
+                })
+                .collect::<Vec<_>>();
 
-        try_join_all(tasks).await?;
+            try_join_all(tasks).await?;
+        }
 
         Ok(())
     }
 
-    pub async fn remove_containers(&self) -> Result<()> {
+    /// Restart the application, stopping then starting all of its containers
+    pub async fn restart(&self) -> Result<()> {
         match self.status().await? {
             AppRunningStatus::NotCreated => bail!("Application's containers are not created yet"),
-            AppRunningStatus::PartiallyCreated | AppRunningStatus::Stopped => {}
+            AppRunningStatus::PartiallyCreated => {
+                bail!("Some of the application's containers have not been created")
+            }
+            AppRunningStatus::Zombie => bail!("At least one container is in zombie mode"),
+            AppRunningStatus::Intermediary => {
+                bail!("At least one container is in an intermediary state")
+            }
+            AppRunningStatus::Stopped
+            | AppRunningStatus::PartiallyRunning
+            | AppRunningStatus::FullyRunning => {}
+        }
+
+        self.stop().await?;
+        self.start().await
+    }
+
+    /// Pause the application's running containers, dependents being paused before their dependencies
+    pub async fn pause(&self) -> Result<()> {
+        let levels = self.existing_containers_by_reverse_deps().await?;
+
+        Self::ensure_can_be_paused_or_unpaused(&levels)?;
+
+        if !levels
+            .iter()
+            .flatten()
+            .any(|(_, existing)| existing.status == ExistingContainerStatus::Running)
+        {
+            bail!("Application is not running");
+        }
+
+        for level in levels {
+            let tasks = level
+                .into_iter()
+                .filter(|(_, existing)| existing.status == ExistingContainerStatus::Running)
+                .map(|(_, existing)| async move {
+                    info!("> Pausing container '{}'...", existing.container_name);
+
+                    docker::pause_container(self.docker, &existing.docker_container_id)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to pause container '{}'", existing.container_name)
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            try_join_all(tasks).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Unpause the application's paused containers, dependencies being unpaused before their dependents
+    pub async fn unpause(&self) -> Result<()> {
+        let levels = self.existing_containers_by_reverse_deps().await?;
+
+        Self::ensure_can_be_paused_or_unpaused(&levels)?;
+
+        if !levels
+            .iter()
+            .flatten()
+            .any(|(_, existing)| existing.status == ExistingContainerStatus::Paused)
+        {
+            bail!("Application is not paused");
+        }
+
+        for level in levels.into_iter().rev() {
+            let tasks = level
+                .into_iter()
+                .filter(|(_, existing)| existing.status == ExistingContainerStatus::Paused)
+                .map(|(_, existing)| async move {
+                    info!("> Unpausing container '{}'...", existing.container_name);
+
+                    docker::unpause_container(self.docker, &existing.docker_container_id)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to unpause container '{}'", existing.container_name)
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            try_join_all(tasks).await?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_can_be_paused_or_unpaused(
+        levels: &[Vec<(Option<&AppContainer>, ExistingAppContainer)>],
+    ) -> Result<()> {
+        let existing_count = levels.iter().map(Vec::len).sum::<usize>();
+
+        if existing_count == 0 {
+            bail!("Application's containers are not created yet");
+        }
+
+        for (_, existing) in levels.iter().flatten() {
+            match existing.status {
+                ExistingContainerStatus::Dead => {
+                    bail!("Container '{}' is in zombie mode", existing.container_name)
+                }
+                ExistingContainerStatus::Restarting | ExistingContainerStatus::Removing => {
+                    bail!(
+                        "Container '{}' is in an intermediary state",
+                        existing.container_name
+                    )
+                }
+                ExistingContainerStatus::Created
+                | ExistingContainerStatus::Running
+                | ExistingContainerStatus::Paused
+                | ExistingContainerStatus::Exited => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the application's containers
+    ///
+    /// Containers are removed in reverse dependency order, dependents being removed before their dependencies.
+    /// Containers of a same dependency level are removed in parallel.
+    /// The application's network is removed afterwards.
+    pub async fn remove_containers(&self) -> Result<()> {
+        match self.status().await? {
+            // Leftover containers may still exist
+            AppRunningStatus::NotCreated
+            | AppRunningStatus::PartiallyCreated
+            | AppRunningStatus::Stopped => {}
             AppRunningStatus::Zombie => bail!("At least one container is in zombie mode"),
             AppRunningStatus::Intermediary => {
                 bail!("At least one container is in an intermediary state")
@@ -259,33 +624,478 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
             AppRunningStatus::PartiallyRunning => {
                 bail!("Some of the application's containers are still running")
             }
-            AppRunningStatus::FullyRunning => return Ok(()),
+            AppRunningStatus::FullyRunning => bail!("Application is running, please stop it first"),
         }
 
-        let containers = self.list_existing_containers().await?;
+        let levels = self.existing_containers_by_reverse_deps().await?;
+
+        if levels.iter().all(Vec::is_empty) {
+            bail!("Application's containers are not created yet");
+        }
 
         let docker = Arc::new(self.docker.clone());
 
-        let tasks = containers
-            .into_iter()
-            .rev()
-            .filter(|container| container.app_id == self.app.id)
-            .map(|container| {
-                let docker = Arc::clone(&docker);
+        for level in levels {
+            let tasks = level
+                .into_iter()
+                .map(|(_, existing)| {
+                    let docker = Arc::clone(&docker);
 
-                async move {
-                    docker::remove_container(&docker, &container.docker_container_id)
-                        .await
-                        .with_context(|| {
-                            format!("Failed to stop container '{}'", container.container_name)
-                        })?;
+                    async move {
+                        info!("> Removing container '{}'...", existing.container_name);
 
-                    Ok::<(), anyhow::Error>(())
+                        docker::remove_container(&docker, &existing.docker_container_id)
+                            .await
+                            .with_context(|| {
+                                format!("Failed to remove container '{}'", existing.container_name)
+                            })?;
+
+                        Ok::<(), anyhow::Error>(())
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            try_join_all(tasks).await?;
+        }
+
+        docker::remove_network(self.docker, &self.app.network_name()).await?;
+
+        // Containers of the dependents sharing it reference it, so it is kept for them
+        if !self.app.is_exposed_on_shared_network(&self.user_data.apps) {
+            docker::remove_network(self.docker, &self.app.shared_network_name()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the application's containers, then its Docker named volumes
+    ///
+    /// Only the disposable volumes are removed if requested.
+    /// Returns the names of the removed volumes.
+    pub async fn destroy(&self, disposable_only: bool) -> Result<Vec<String>> {
+        // Fails if any of the containers is still running
+        if !self.list_existing_containers().await?.is_empty() {
+            self.remove_containers().await?;
+        }
+
+        let mut removed = vec![];
+
+        for volume in self.app.list_volumes(self.env) {
+            if volume.storage != AppVolumeStorage::DockerVolume
+                || (disposable_only && !volume.disposable)
+            {
+                continue;
+            }
+
+            if docker::remove_volume(self.docker, &volume.location).await? {
+                removed.push(volume.location);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Start a single container, after starting the containers it depends on
+    pub async fn start_app_container(&self, name: &str) -> Result<ContainerActionReport> {
+        let container = self.find_app_container(name)?;
+
+        let mut to_start = transitive_dependencies(&self.app.containers, name)?;
+        to_start.push(container);
+
+        let existing = self.list_existing_containers().await?;
+
+        let mut report = ContainerActionReport::default();
+
+        for container in to_start {
+            let infos = existing
+                .iter()
+                .find(|c| c.container_id == container.id)
+                .with_context(|| format!("Container '{}' is not created yet", container.name))?;
+
+            match infos.status {
+                ExistingContainerStatus::Running => continue,
+                ExistingContainerStatus::Created | ExistingContainerStatus::Exited => {}
+                ExistingContainerStatus::Dead => {
+                    bail!("Container '{}' is in zombie mode", container.name)
                 }
+                ExistingContainerStatus::Paused
+                | ExistingContainerStatus::Restarting
+                | ExistingContainerStatus::Removing => {
+                    bail!("Container '{}' is in an intermediary state", container.name)
+                }
+            }
+
+            info!("> Starting container '{}'...", container.name);
+
+            docker::start_container(self.docker, &infos.docker_container_id)
+                .await
+                .with_context(|| format!("Failed to start container '{}'", container.name))?;
+
+            report.started.push(container.name.clone());
+        }
+
+        Ok(report)
+    }
+
+    /// Stop a single container
+    ///
+    /// If `stop_dependents` is set, the running containers depending on it are stopped first.
+    /// Otherwise, they are left running and reported as warnings.
+    pub async fn stop_app_container(
+        &self,
+        name: &str,
+        stop_dependents: bool,
+    ) -> Result<ContainerActionReport> {
+        let container = self.find_app_container(name)?;
+
+        let existing = self.list_existing_containers().await?;
+
+        let is_running = |container: &AppContainer| {
+            existing.iter().any(|c| {
+                c.container_id == container.id && c.status == ExistingContainerStatus::Running
             })
+        };
+
+        let running_dependents = transitive_dependents(&self.app.containers, name)?
+            .into_iter()
+            .filter(|dependent| is_running(dependent))
             .collect::<Vec<_>>();
 
-        try_join_all(tasks).await?;
+        let mut report = ContainerActionReport::default();
+
+        let mut to_stop = vec![];
+
+        if stop_dependents {
+            to_stop.extend(running_dependents.into_iter().rev());
+        } else {
+            for dependent in running_dependents {
+                report.warnings.push(format!(
+                    "Container '{}' depends on '{}' and is still running",
+                    dependent.name, container.name
+                ));
+            }
+        }
+
+        if is_running(container) {
+            to_stop.push(container);
+        }
+
+        for container in to_stop {
+            info!("> Stopping container '{}'...", container.name);
+
+            docker::stop_container(
+                self.docker,
+                &container.docker_container_name(),
+                container.stop_timeout,
+            )
+            .await
+            .with_context(|| format!("Failed to stop container '{}'", container.name))?;
+
+            report.stopped.push(container.name.clone());
+        }
+
+        Ok(report)
+    }
+
+    /// Restart a single container
+    ///
+    /// If `restart_dependents` is set, the running containers depending on it are restarted as well.
+    pub async fn restart_app_container(
+        &self,
+        name: &str,
+        restart_dependents: bool,
+    ) -> Result<ContainerActionReport> {
+        let stop_report = self.stop_app_container(name, restart_dependents).await?;
+
+        let mut report = self.start_app_container(name).await?;
+
+        for dependent in &stop_report.stopped {
+            if dependent != name {
+                report
+                    .started
+                    .extend(self.start_app_container(dependent).await?.started);
+            }
+        }
+
+        report.stopped = stop_report.stopped;
+        report.warnings = stop_report.warnings;
+
+        Ok(report)
+    }
+
+    fn find_app_container(&self, name: &str) -> Result<&'c AppContainer> {
+        self.app.get_container(name).with_context(|| {
+            format!(
+                "Application '{}' has no container named '{name}'",
+                self.app.name
+            )
+        })
+    }
+
+    /// Get the application's existing containers grouped by dependency levels, dependents coming first
+    ///
+    /// Leftover containers which don't belong to the application's current containers
+    /// (e.g. created before an update) come first, as nothing depends on them anymore.
+    async fn existing_containers_by_reverse_deps(
+        &self,
+    ) -> Result<Vec<Vec<(Option<&'c AppContainer>, ExistingAppContainer)>>> {
+        let mut existing = self.list_existing_containers().await?;
+
+        let levels = self.app.dependency_levels().with_context(|| {
+            format!(
+                "Failed to sort containers of application '{}' by dependencies",
+                self.app.name
+            )
+        })?;
+
+        let mut sorted = vec![];
+
+        for level in levels.into_iter().rev() {
+            let mut sorted_level = vec![];
+
+            for container in level {
+                if let Some(pos) = existing.iter().position(|c| c.container_id == container.id) {
+                    sorted_level.push((Some(container), existing.swap_remove(pos)));
+                }
+            }
+
+            sorted.push(sorted_level);
+        }
+
+        if !existing.is_empty() {
+            sorted.insert(0, existing.into_iter().map(|c| (None, c)).collect());
+        }
+
+        Ok(sorted)
+    }
+
+    /// Connect the application's containers to its shared network, so its dependents can reach them
+    ///
+    /// Each container can be reached using its name as hostname.
+    /// Containers are connected on creation when a dependent shares the network,
+    /// this handles the ones created before the dependency was declared.
+    pub async fn expose_on_shared_network(&self) -> Result<()> {
+        let network = self.app.shared_network_name();
+
+        docker::ensure_network(self.docker, &network).await?;
+
+        for existing in self.list_existing_containers().await? {
+            let Some(container) = self
+                .app
+                .containers
+                .iter()
+                .find(|c| c.id == existing.container_id)
+            else {
+                continue;
+            };
+
+            if container
+                .privileges
+                .as_ref()
+                .is_some_and(|privileges| privileges.network_mode == ContainerNetworkMode::Host)
+            {
+                continue;
+            }
+
+            docker::connect_to_network(
+                self.docker,
+                &network,
+                &existing.docker_container_id,
+                vec![container.name.clone()],
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to expose container '{}' on the application's shared network",
+                    container.name
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Connect the existing containers to the networks shared by new dependencies,
+    /// and disconnect them from the ones of the dependencies they don't have anymore
+    ///
+    /// Networks of the new dependencies must exist, see [`AppRunner::expose_on_shared_network`].
+    pub async fn update_dependencies_networks(&self, previous: &App) -> Result<()> {
+        let networks = self.app.dependencies_networks();
+        let previous_networks = previous.dependencies_networks();
+
+        for existing in self.list_existing_containers().await? {
+            let Some(container) = self
+                .app
+                .containers
+                .iter()
+                .find(|c| c.id == existing.container_id)
+            else {
+                continue;
+            };
+
+            if container
+                .privileges
+                .as_ref()
+                .is_some_and(|privileges| privileges.network_mode == ContainerNetworkMode::Host)
+            {
+                continue;
+            }
+
+            for network in networks.iter().filter(|n| !previous_networks.contains(n)) {
+                docker::connect_to_network(
+                    self.docker,
+                    network,
+                    &existing.docker_container_id,
+                    vec![],
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to connect container '{}' to a dependency's shared network",
+                        container.name
+                    )
+                })?;
+            }
+
+            for network in previous_networks.iter().filter(|n| !networks.contains(n)) {
+                docker::disconnect_from_network(self.docker, network, &existing.docker_container_id)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to disconnect container '{}' from a former dependency's shared network",
+                            container.name
+                        )
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List the directories of the external volumes mounted by a container, including shared ones
+    fn external_volume_dirs<'d>(
+        &'d self,
+        container: &'d AppContainer,
+    ) -> Vec<(PathBuf, Option<&'d VolumeOwnership>)> {
+        let mut dirs = vec![];
+
+        for volume in &container.volumes {
+            match &volume.variant {
+                AppVolumeType::External { .. } => dirs.push((
+                    self.env
+                        .app_container_internal_volume_dir(&container.identity(), &volume.name),
+                    volume.ownership.as_ref(),
+                )),
+
+                AppVolumeType::Shared { shared_volume, .. } => {
+                    if let Some(shared) = self.app.get_shared_volume(shared_volume) {
+                        if shared.variant == AppSharedVolumeType::External {
+                            dirs.push((
+                                self.env
+                                    .app_shared_volume_dir(&self.app.identity(), shared_volume),
+                                shared.ownership.as_ref(),
+                            ));
+                        }
+                    }
+                }
+
+                AppVolumeType::Disposable { .. }
+                | AppVolumeType::Internal { .. }
+                | AppVolumeType::BindToPath { .. }
+                | AppVolumeType::GlobalPath { .. } => {}
+            }
+        }
+
+        dirs
+    }
+
+    /// Create the directories of the external volumes mounted by a container, with their declared ownership
+    ///
+    /// Otherwise Docker creates them as root, which prevents non-root images from writing to them.
+    fn prepare_external_volumes(&self, container: &AppContainer) -> Result<()> {
+        for (dir, ownership) in self.external_volume_dirs(container) {
+            fs::create_dir_all(&dir).with_context(|| {
+                format!(
+                    "Failed to create volume directory '{}' of container '{}'",
+                    dir.display(),
+                    container.name
+                )
+            })?;
+
+            if let Some(ownership) = ownership {
+                apply_ownership(&dir, ownership.uid, ownership.gid, ownership.parsed_mode()?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-apply the declared ownership on the existing directories of the application's external volumes
+    ///
+    /// Returns the paths of the directories the ownership was applied to.
+    pub fn repair_volumes_ownership(&self) -> Result<Vec<String>> {
+        let mut repaired = vec![];
+
+        for container in &self.app.containers {
+            for (dir, ownership) in self.external_volume_dirs(container) {
+                let Some(ownership) = ownership else {
+                    continue;
+                };
+
+                if !dir.is_dir() {
+                    continue;
+                }
+
+                let path = dir.to_string_lossy().into_owned();
+
+                if repaired.contains(&path) {
+                    continue;
+                }
+
+                info!("> Applying ownership to '{path}'...");
+
+                apply_ownership(&dir, ownership.uid, ownership.gid, ownership.parsed_mode()?)?;
+
+                repaired.push(path);
+            }
+        }
+
+        Ok(repaired)
+    }
+
+    /// Delete the application's directory and Docker named volumes
+    ///
+    /// The application's containers must have been destroyed first.
+    pub async fn purge_data(&self) -> Result<()> {
+        if self.status().await? != AppRunningStatus::NotCreated {
+            bail!("All containers must be destroyed before purging the application's data");
+        }
+
+        info!(
+            "Purging data of application '{}' [{}]...",
+            self.app.name, self.app.id
+        );
+
+        for volume in self.app.list_volumes(self.env) {
+            if volume.storage == AppVolumeStorage::DockerVolume {
+                docker::remove_volume(self.docker, &volume.location).await?;
+            }
+        }
+
+        docker::remove_network(self.docker, &self.app.shared_network_name()).await?;
+
+        let app_dir = self.env.app_dir(&self.app.identity());
+
+        if app_dir.exists() {
+            info!("> Removing directory '{}'...", app_dir.display());
+
+            fs::remove_dir_all(&app_dir).with_context(|| {
+                format!(
+                    "Failed to remove the application's directory '{}'",
+                    app_dir.display()
+                )
+            })?;
+        }
 
         Ok(())
     }
@@ -298,11 +1108,105 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
         Ok(())
     }
 
+    /// Copy the content of the application's volumes into a clone of it
+    ///
+    /// Disposable volumes and bindings to host or global paths are not copied.
+    pub async fn copy_data_to(&self, clone: &App) -> Result<()> {
+        match self.status().await? {
+            AppRunningStatus::NotCreated | AppRunningStatus::Stopped => {}
+            _ => bail!("Please stop the application before copying its data"),
+        }
+
+        for container in &self.app.containers {
+            let cloned = clone.get_container(&container.name).with_context(|| {
+                format!("Container '{}' was not found in clone", container.name)
+            })?;
+
+            for volume in &container.volumes {
+                match &volume.variant {
+                    AppVolumeType::Disposable { .. }
+                    | AppVolumeType::BindToPath { .. }
+                    | AppVolumeType::GlobalPath { .. }
+                    | AppVolumeType::Shared { .. } => {}
+
+                    AppVolumeType::Internal { .. } => {
+                        docker::copy_volume(
+                            self.docker,
+                            &container.get_docker_volume_name(&volume.name),
+                            &cloned.get_docker_volume_name(&volume.name),
+                        )
+                        .await?
+                    }
+
+                    AppVolumeType::External { .. } => {
+                        let from = self
+                            .env
+                            .app_container_internal_volume_dir(&container.identity(), &volume.name);
+
+                        if from.exists() {
+                            copy_dir(
+                                &from,
+                                &self.env.app_container_internal_volume_dir(
+                                    &cloned.identity(),
+                                    &volume.name,
+                                ),
+                            )
+                            .with_context(|| {
+                                format!(
+                                    "Failed to copy volume '{}' of container '{}'",
+                                    volume.name, container.name
+                                )
+                            })?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for volume in &self.app.shared_volumes {
+            match volume.variant {
+                AppSharedVolumeType::Disposable => {}
+
+                AppSharedVolumeType::Internal => {
+                    docker::copy_volume(
+                        self.docker,
+                        &self.app.get_docker_shared_volume_name(&volume.name),
+                        &clone.get_docker_shared_volume_name(&volume.name),
+                    )
+                    .await?
+                }
+
+                AppSharedVolumeType::External => {
+                    let from = self
+                        .env
+                        .app_shared_volume_dir(&self.app.identity(), &volume.name);
+
+                    if from.exists() {
+                        copy_dir(
+                            &from,
+                            &self
+                                .env
+                                .app_shared_volume_dir(&clone.identity(), &volume.name),
+                        )
+                        .with_context(|| {
+                            format!("Failed to copy shared volume '{}'", volume.name)
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn generate_app_template(&self) -> AppTemplate {
         self.app.clone().to_template()
     }
 
-    fn generate_container_config(&self, container: &AppContainer) -> ContainerCreationConfig {
+    fn generate_container_config(
+        &self,
+        container: &AppContainer,
+    ) -> Result<ContainerCreationConfig> {
         assert_eq!(
             container.app.id, self.app.id,
             "Assertion error: tried to generate a container's configuration for another app in runner"
@@ -340,13 +1244,103 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
                     in_container: container_path.clone(),
                     readonly: *readonly
                 }),
+
+                AppVolumeType::Shared {
+                    shared_volume,
+                    container_path,
+                    readonly,
+                } => {
+                    let shared = self.app.get_shared_volume(shared_volume).with_context(|| format!("Volume '{}' references undeclared shared volume '{shared_volume}'", volume.name))?;
+
+                    mounts.push(ContainerMount {
+                        in_host: match shared.variant {
+                            AppSharedVolumeType::Disposable | AppSharedVolumeType::Internal => self.app.get_docker_shared_volume_name(shared_volume),
+                            AppSharedVolumeType::External => self.env.app_shared_volume_dir(&self.app.identity(), shared_volume).to_str().expect("Internal error: normalized app's shared volume path contains invalid UTF-8 characters").to_string(),
+                        },
+                        in_container: container_path.clone(),
+                        readonly: *readonly
+                    })
+                }
+
+                AppVolumeType::GlobalPath {
+                    global_path_id,
+                    container_path,
+                    readonly,
+                } => mounts.push(ContainerMount {
+                    in_host: find_global_path(&self.user_data.global_paths, global_path_id)
+                        .with_context(|| format!("Volume '{}' references undefined global path '{global_path_id}'", volume.name))?
+                        .host_path
+                        .clone(),
+                    in_container: container_path.clone(),
+                    readonly: *readonly
+                }),
             }
         }
 
-        ContainerCreationConfig {
+        let env = container
+            .env_vars
+            .iter()
+            .map(|var| {
+                // Secret values are used as is, so they are only decrypted
+                let value = if var.secret == Some(true) {
+                    self.env.secrets_key.decrypt(&var.value)
+                } else {
+                    self.app
+                        .resolve_references(&var.value)
+                        .and_then(|value| self.app.inject_secrets(&value, &self.env.secrets_key))
+                }
+                .with_context(|| {
+                    format!(
+                        "Failed to resolve the value of environment variable '{}'",
+                        var.name
+                    )
+                })?;
+
+                Ok(ContainerEnvironmentVar {
+                    name: var.name.clone(),
+                    value,
+                    secret: var.secret,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let privileges = container.privileges.clone().unwrap_or_default();
+
+        // Containers using the host's network cannot be connected to other networks
+        let networks = if privileges.network_mode == ContainerNetworkMode::Host {
+            vec![]
+        } else {
+            // Containers of the application reach each other using their Docker container name
+            let own = ContainerNetwork {
+                name: self.app.network_name(),
+                aliases: vec![container.docker_container_name()],
+            };
+
+            let deps = self
+                .app
+                .dependencies_networks()
+                .into_iter()
+                .map(|name| ContainerNetwork {
+                    name,
+                    aliases: vec![],
+                });
+
+            // Dependents sharing the application's network reach its containers using their name
+            let shared = self
+                .app
+                .is_exposed_on_shared_network(&self.user_data.apps)
+                .then(|| ContainerNetwork {
+                    name: self.app.shared_network_name(),
+                    aliases: vec![container.name.clone()],
+                });
+
+            std::iter::once(own).chain(shared).chain(deps).collect()
+        };
+
+        Ok(ContainerCreationConfig {
             name: container.docker_container_name(),
             image: container.image.clone(),
-            env: container.env_vars.clone(),
+            env,
             port_bindings: container.port_bindings.clone(),
             mounts,
             labels: HashMap::from([
@@ -356,24 +1350,33 @@ impl<'a, 'b, 'c> AppRunner<'a, 'b, 'c> {
                 (CONTAINER_NAME_LABEL.to_string(), container.name.clone()),
             ]),
             restart_policy: ContainerRestartPolicy::UnlessStopped,
-        }
+            stop_timeout: container.stop_timeout,
+            privileges,
+            networks,
+        })
     }
 
-    fn sort_containers_by_deps(&self) -> Vec<&AppContainer> {
-        let mut refs: Vec<_> = self.app.containers.iter().collect();
+    fn sort_containers_by_deps(&self) -> Result<Vec<&'c AppContainer>> {
+        self.app.containers_by_deps().with_context(|| {
+            format!(
+                "Failed to sort containers of application '{}' by dependencies",
+                self.app.name
+            )
+        })
+    }
+}
 
-        refs.sort_by(|a, b| {
-            if a.depends_on.contains(&b.name) {
-                Ordering::Greater
-            } else if b.depends_on.contains(&a.name) {
-                Ordering::Less
-            } else {
-                a.name.cmp(&b.name)
-            }
-        });
+/// Report of an action performed on some of an application's containers
+#[derive(SimpleObject, Default)]
+pub struct ContainerActionReport {
+    /// Names of the containers that were started
+    pub started: Vec<String>,
 
-        refs
-    }
+    /// Names of the containers that were stopped
+    pub stopped: Vec<String>,
+
+    /// Potential issues the action didn't solve
+    pub warnings: Vec<String>,
 }
 
 #[derive(Enum, Clone, Copy, PartialEq, Eq)]
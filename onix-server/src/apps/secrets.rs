@@ -0,0 +1,185 @@
+use anyhow::{bail, Context, Result};
+use async_graphql::{Enum, InputObject, SimpleObject};
+use once_cell::sync::Lazy;
+use rand::{seq::SliceRandom, thread_rng};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{encryption::SecretsKey, NAME_VALIDATOR};
+
+/// Reference to a generated secret, e.g. '${secrets.db_password}'
+static SECRET_REF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{secrets\.([a-zA-Z0-9_-]+)\}").unwrap());
+
+static ALPHANUMERIC: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+static HEX: &str = "0123456789abcdef";
+static BASE64: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+static MAX_SECRET_LENGTH: u32 = 4096;
+
+/// Secret generated by Onix when an application is created from a template
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppTemplateSecret {
+    pub name: String,
+
+    /// Number of characters to generate
+    pub length: u32,
+
+    pub format: SecretFormat,
+
+    /// Characters to pick from, only for the 'Custom' format
+    pub alphabet: Option<String>,
+}
+
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SecretFormat {
+    Alphanumeric,
+    Hex,
+    Base64,
+    Custom,
+}
+
+impl AppTemplateSecret {
+    pub fn validate(&self) -> Result<()> {
+        if !NAME_VALIDATOR.is_match(&self.name) {
+            bail!(
+                "Invalid secret name '{}', please follow regex: {}",
+                self.name,
+                NAME_VALIDATOR.as_str()
+            );
+        }
+
+        if self.length == 0 || self.length > MAX_SECRET_LENGTH {
+            bail!(
+                "Length of secret '{}' must be between 1 and {MAX_SECRET_LENGTH}",
+                self.name
+            );
+        }
+
+        match (self.format, &self.alphabet) {
+            (SecretFormat::Custom, None) => {
+                bail!("Please provide an alphabet for custom secret '{}'", self.name)
+            }
+            (SecretFormat::Custom, Some(alphabet)) if alphabet.is_empty() => {
+                bail!("Please provide a non-empty alphabet for secret '{}'", self.name)
+            }
+            (SecretFormat::Custom, Some(_)) => {}
+            (_, Some(_)) => bail!(
+                "An alphabet can only be provided for secrets with the 'Custom' format (secret '{}')",
+                self.name
+            ),
+            (_, None) => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn generate(&self, key: &SecretsKey) -> Result<AppSecret> {
+        self.validate()?;
+
+        let alphabet = match self.format {
+            SecretFormat::Alphanumeric => ALPHANUMERIC.chars().collect::<Vec<_>>(),
+            SecretFormat::Hex => HEX.chars().collect(),
+            SecretFormat::Base64 => BASE64.chars().collect(),
+            SecretFormat::Custom => self
+                .alphabet
+                .as_ref()
+                .context("Missing alphabet for custom secret")?
+                .chars()
+                .collect(),
+        };
+
+        let mut rng = thread_rng();
+
+        let value = (0..self.length)
+            .map(|_| {
+                *alphabet
+                    .choose(&mut rng)
+                    .expect("Assertion error: secret's alphabet is empty")
+            })
+            .collect::<String>();
+
+        Ok(AppSecret {
+            declaration: self.clone(),
+            value: key.encrypt(&value)?,
+        })
+    }
+}
+
+/// A generated secret, stored with its application
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AppSecret {
+    #[serde(flatten)]
+    pub declaration: AppTemplateSecret,
+
+    /// Encrypted value
+    pub value: String,
+}
+
+/// Generate the secrets declared by a template, keeping the value of already-existing ones
+pub fn generate_secrets(
+    declarations: Vec<AppTemplateSecret>,
+    existing: &[AppSecret],
+    key: &SecretsKey,
+) -> Result<Vec<AppSecret>> {
+    let mut secrets = Vec::<AppSecret>::with_capacity(declarations.len());
+
+    for declaration in declarations {
+        if secrets
+            .iter()
+            .any(|secret| secret.declaration.name == declaration.name)
+        {
+            bail!("Secret '{}' was declared twice", declaration.name);
+        }
+
+        declaration.validate()?;
+
+        let secret = match existing
+            .iter()
+            .find(|secret| secret.declaration.name == declaration.name)
+        {
+            Some(secret) => AppSecret {
+                declaration,
+                value: secret.value.clone(),
+            },
+            None => declaration.generate(key)?,
+        };
+
+        secrets.push(secret);
+    }
+
+    Ok(secrets)
+}
+
+/// List the names of the secrets referenced in a value
+pub fn referenced_secrets(value: &str) -> impl Iterator<Item = &str> {
+    SECRET_REF
+        .captures_iter(value)
+        .map(|captures| captures.get(1).unwrap().as_str())
+}
+
+/// Replace secrets references in a value with their actual value
+pub fn inject_secrets(value: &str, secrets: &[AppSecret], key: &SecretsKey) -> Result<String> {
+    let mut injected = String::with_capacity(value.len());
+    let mut last_end = 0;
+
+    for captures in SECRET_REF.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let name = &captures[1];
+
+        let secret = secrets
+            .iter()
+            .find(|secret| secret.declaration.name == name)
+            .with_context(|| format!("Reference to unknown secret '{name}'"))?;
+
+        injected.push_str(&value[last_end..whole.start()]);
+        injected.push_str(&key.reveal(&secret.value)?);
+
+        last_end = whole.end();
+    }
+
+    injected.push_str(&value[last_end..]);
+
+    Ok(injected)
+}
@@ -1,18 +1,40 @@
-use async_graphql::{InputObject, SimpleObject};
+use async_graphql::{Enum, InputObject, SimpleObject};
 use serde::{Deserialize, Serialize};
 
 use crate::graphql_enum;
 
-pub use crate::docker::{ContainerEnvironmentVar, ContainerPortBinding};
+use super::{AppMetadata, AppTemplateParam, AppTemplateSecret};
+
+pub use crate::docker::{ContainerEnvironmentVar, ContainerPortBinding, ContainerPrivileges};
 
 #[derive(SimpleObject, InputObject, Serialize, Deserialize)]
 #[graphql(input_name_suffix = "Input")]
 pub struct AppTemplate {
     pub name: String,
+
+    /// Identifier of the template, unique in its store
+    pub id: Option<String>,
+
+    /// Version of the template (semver)
+    pub version: Option<String>,
+
     pub containers: Vec<AppContainerTemplate>,
+
+    /// Volumes which can be mounted by several containers of the application
+    pub shared_volumes: Option<Vec<AppSharedVolume>>,
+
+    /// Informations displayed about the application (description, icon, links, ...)
+    pub metadata: Option<AppMetadata>,
+
+    /// Parameters to provide when creating an application from this template
+    pub params: Option<Vec<AppTemplateParam>>,
+
+    /// Secrets to generate when creating an application from this template,
+    /// referenced in environment variables as '${secrets.<name>}'
+    pub secrets: Option<Vec<AppTemplateSecret>>,
 }
 
-#[derive(SimpleObject, InputObject, Serialize, Deserialize)]
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, PartialEq)]
 #[graphql(input_name_suffix = "Input")]
 pub struct AppContainerTemplate {
     pub name: String,
@@ -21,17 +43,58 @@ pub struct AppContainerTemplate {
     pub port_bindings: Vec<ContainerPortBinding>,
     pub volumes: Vec<AppVolume>,
     pub depends_on: Vec<String>,
+    pub privileges: Option<ContainerPrivileges>,
+
+    /// Time to wait for the container to stop before killing it, in seconds
+    pub stop_timeout: Option<u32>,
 }
 
-#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone)]
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
 #[graphql(input_name_suffix = "Input")]
 pub struct AppVolume {
     pub name: String,
     pub variant: AppVolumeType,
+
+    /// Ownership of the volume's directory, only for 'External' volumes
+    pub ownership: Option<VolumeOwnership>,
+}
+
+/// Volume declared at the application level, which its containers can mount
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct AppSharedVolume {
+    pub name: String,
+    pub variant: AppSharedVolumeType,
+
+    /// Ownership of the volume's directory, only for 'External' volumes
+    pub ownership: Option<VolumeOwnership>,
+}
+
+/// Ownership applied to a volume's directory when it is created
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct VolumeOwnership {
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+
+    /// Permissions in octal notation, e.g. '750'
+    pub mode: Option<String>,
+}
+
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AppSharedVolumeType {
+    /// Volume that could be dropped without any real data loss
+    Disposable,
+
+    /// Docker volume storing data which does not need to be modifiable by the end user
+    Internal,
+
+    /// Volume stored in an accessible filesystem
+    External,
 }
 
 graphql_enum!(
-    #[derive(Serialize, Deserialize)]
+    #[derive(Serialize, Deserialize, PartialEq)]
     pub enum AppVolumeType {
         /// Volume that could be dropped without any real datal loss
         /// (e.g. cache or unimportant configuration files)
@@ -53,11 +116,19 @@ graphql_enum!(
             container_path: String,
             readonly: bool,
         },
-        // TODO: /// Binding to a global path
-        // #[derive(PartialEq, Eq, Hash)]
-        // GlobalPath {
-        //     global_path_id: String,
-        //     readonly: bool,
-        // },
+
+        /// Binding to a global path, registered by the administrator under an identifier
+        GlobalPath {
+            global_path_id: String,
+            container_path: String,
+            readonly: bool,
+        },
+
+        /// Mount of one of the application's shared volumes
+        Shared {
+            shared_volume: String,
+            container_path: String,
+            readonly: bool,
+        },
     }
 );
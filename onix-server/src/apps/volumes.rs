@@ -0,0 +1,204 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use anyhow::{bail, Context, Result};
+use async_graphql::{Enum, SimpleObject};
+
+use crate::docker::NAME_PREFIX;
+
+use super::{
+    app::App, env::AppRunnerEnvironment, AppContainerTemplate, AppSharedVolume,
+    AppSharedVolumeType, AppVolumeType, VolumeOwnership, NAME_VALIDATOR,
+};
+
+/// Volume whose storage is managed by Onix
+#[derive(SimpleObject)]
+pub struct AppVolumeInfo {
+    pub name: String,
+
+    /// Name of the container the volume belongs to, absent for shared volumes
+    pub container: Option<String>,
+
+    /// Indicates if the volume can be dropped without any real data loss
+    pub disposable: bool,
+
+    pub storage: AppVolumeStorage,
+
+    /// Name of the Docker volume, or path of the directory on the host
+    pub location: String,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum AppVolumeStorage {
+    DockerVolume,
+    Directory,
+}
+
+impl App {
+    pub fn get_shared_volume(&self, name: &str) -> Option<&AppSharedVolume> {
+        self.shared_volumes
+            .iter()
+            .find(|volume| volume.name == name)
+    }
+
+    pub fn get_docker_shared_volume_name(&self, volume: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        volume.hash(&mut hasher);
+
+        format!(
+            "{NAME_PREFIX}{}_shared_{}",
+            self.id.encode(),
+            base62::encode(hasher.finish())
+        )
+    }
+
+    /// List the volumes of the application whose storage is managed by Onix
+    ///
+    /// Bindings to host and global paths are not included.
+    pub fn list_volumes(&self, env: &AppRunnerEnvironment) -> Vec<AppVolumeInfo> {
+        let shared = self.shared_volumes.iter().map(|volume| {
+            let (storage, location) = match volume.variant {
+                AppSharedVolumeType::Disposable | AppSharedVolumeType::Internal => (
+                    AppVolumeStorage::DockerVolume,
+                    self.get_docker_shared_volume_name(&volume.name),
+                ),
+
+                AppSharedVolumeType::External => (
+                    AppVolumeStorage::Directory,
+                    env.app_shared_volume_dir(&self.identity(), &volume.name)
+                        .to_string_lossy()
+                        .into_owned(),
+                ),
+            };
+
+            AppVolumeInfo {
+                name: volume.name.clone(),
+                container: None,
+                disposable: volume.variant == AppSharedVolumeType::Disposable,
+                storage,
+                location,
+            }
+        });
+
+        let owned = self.containers.iter().flat_map(|container| {
+            container.volumes.iter().filter_map(|volume| {
+                let (disposable, storage, location) = match &volume.variant {
+                    AppVolumeType::Disposable { .. } => (
+                        true,
+                        AppVolumeStorage::DockerVolume,
+                        container.get_docker_volume_name(&volume.name),
+                    ),
+
+                    AppVolumeType::Internal { .. } => (
+                        false,
+                        AppVolumeStorage::DockerVolume,
+                        container.get_docker_volume_name(&volume.name),
+                    ),
+
+                    AppVolumeType::External { .. } => (
+                        false,
+                        AppVolumeStorage::Directory,
+                        env.app_container_internal_volume_dir(&container.identity(), &volume.name)
+                            .to_string_lossy()
+                            .into_owned(),
+                    ),
+
+                    AppVolumeType::BindToPath { .. }
+                    | AppVolumeType::GlobalPath { .. }
+                    | AppVolumeType::Shared { .. } => return None,
+                };
+
+                Some(AppVolumeInfo {
+                    name: volume.name.clone(),
+                    container: Some(container.name.clone()),
+                    disposable,
+                    storage,
+                    location,
+                })
+            })
+        });
+
+        shared.chain(owned).collect()
+    }
+}
+
+/// Ensure shared volumes are properly declared and that containers only mount declared ones
+pub fn check_shared_volumes(
+    shared_volumes: &[AppSharedVolume],
+    containers: &[AppContainerTemplate],
+) -> Result<()> {
+    for (i, volume) in shared_volumes.iter().enumerate() {
+        if !NAME_VALIDATOR.is_match(&volume.name) {
+            bail!(
+                "Invalid shared volume name '{}', please follow regex: {}",
+                volume.name,
+                NAME_VALIDATOR.as_str()
+            );
+        }
+
+        if shared_volumes[..i]
+            .iter()
+            .any(|other| other.name == volume.name)
+        {
+            bail!("Shared volume '{}' was declared twice", volume.name);
+        }
+
+        if let Some(ownership) = &volume.ownership {
+            if volume.variant != AppSharedVolumeType::External {
+                bail!(
+                    "Ownership can only be set on external volumes (shared volume '{}')",
+                    volume.name
+                );
+            }
+
+            ownership.validate().with_context(|| {
+                format!("Invalid ownership for shared volume '{}'", volume.name)
+            })?;
+        }
+    }
+
+    for container in containers {
+        for volume in &container.volumes {
+            if let AppVolumeType::Shared { shared_volume, .. } = &volume.variant {
+                if !shared_volumes
+                    .iter()
+                    .any(|other| &other.name == shared_volume)
+                {
+                    bail!(
+                        "Volume '{}' of container '{}' references undeclared shared volume '{shared_volume}'",
+                        volume.name,
+                        container.name
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl VolumeOwnership {
+    pub fn validate(&self) -> Result<()> {
+        self.parsed_mode()?;
+        Ok(())
+    }
+
+    /// Parse the permissions from their octal notation
+    pub fn parsed_mode(&self) -> Result<Option<u32>> {
+        let Some(mode) = &self.mode else {
+            return Ok(None);
+        };
+
+        let parsed = u32::from_str_radix(mode, 8).with_context(|| {
+            format!("Invalid mode '{mode}', expected octal notation (e.g. '750')")
+        })?;
+
+        if parsed > 0o7777 {
+            bail!("Invalid mode '{mode}', must not exceed '7777'");
+        }
+
+        Ok(Some(parsed))
+    }
+}
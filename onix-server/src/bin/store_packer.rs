@@ -1,13 +1,13 @@
 #![forbid(unsafe_code)]
 #![forbid(unused_must_use)]
 
-use onix_server::stores::StoreContent;
+use onix_server::{apps::load_template_env_files, stores::StoreContent};
 
 use std::{fs, path::PathBuf};
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use log::{error, info, LevelFilter};
+use log::{error, info, warn, LevelFilter};
 
 #[derive(Parser)]
 #[clap(about, version, author, about = "Create a packed store from YAML")]
@@ -59,9 +59,27 @@ fn inner_main(cmd: Cmd) -> Result<()> {
 
     info!("> Parsing the input file...");
 
-    let store: StoreContent =
+    let mut store: StoreContent =
         serde_yaml::from_str(&input).context("Failed to deserialize YAML file")?;
 
+    info!("> Loading environment files...");
+
+    let base_dir = input_file
+        .parent()
+        .context("Failed to get the input file's parent directory")?;
+
+    for app in store.list_apps_mut() {
+        let id = app.template_id().unwrap_or("<no ID>").to_string();
+
+        let warnings = load_template_env_files(app.raw_mut(), base_dir).with_context(|| {
+            format!("Failed to load environment files for application template '{id}'")
+        })?;
+
+        for warning in warnings {
+            warn!("| Application template '{id}': {warning}");
+        }
+    }
+
     info!("> Compressing...");
 
     let compressed = store.compress().context("Failed to compress the store")?;
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use log::LevelFilter;
 
@@ -10,6 +12,27 @@ pub struct Cmd {
     #[clap(short, long, help = "Port to run the server on")]
     pub port: Option<u16>,
 
+    #[clap(
+        long,
+        help = "Hostname the local machine is reachable at, used in links to applications' web interfaces"
+    )]
+    pub public_hostname: Option<String>,
+
+    #[clap(
+        long = "docker-host",
+        help = "Named Docker endpoint, e.g. 'nas=unix:///var/run/docker.sock' (can be repeated)"
+    )]
+    pub docker_hosts: Vec<String>,
+
+    #[clap(long, help = "Name of the Docker host to use when none is specified")]
+    pub default_docker_host: Option<String>,
+
+    #[clap(
+        long,
+        help = "File containing the passphrase to derive the secrets encryption key from (defaults to a key stored in the data directory)"
+    )]
+    pub secrets_passphrase_file: Option<PathBuf>,
+
     #[clap(short, long, help = "Logging level")]
     pub logging_level: Option<LevelFilter>,
 }
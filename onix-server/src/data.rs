@@ -1,8 +1,59 @@
+use async_graphql::{Enum, SimpleObject};
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
-use crate::apps::App;
+use crate::apps::{App, GlobalPath};
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct UserData {
     pub apps: Vec<App>,
+
+    /// Named host paths which can be mounted by applications
+    #[serde(default)]
+    pub global_paths: Vec<GlobalPath>,
+
+    /// Removed applications whose data was kept, so they can be restored later
+    #[serde(default)]
+    pub archived_apps: Vec<ArchivedApp>,
+}
+
+impl UserData {
+    /// Bind the applications saved before Docker hosts were introduced to the default one
+    ///
+    /// Returns `true` if any application was modified.
+    pub fn fill_missing_hosts(&mut self, default_docker_host: &str) -> bool {
+        let mut filled = false;
+
+        let apps = self.apps.iter_mut().chain(
+            self.archived_apps
+                .iter_mut()
+                .map(|archived| &mut archived.app),
+        );
+
+        for app in apps {
+            if app.host.is_empty() {
+                app.host = default_docker_host.to_string();
+                filled = true;
+            }
+        }
+
+        filled
+    }
+}
+
+/// Application removed while keeping its data (directory and Docker volumes)
+#[derive(SimpleObject, Serialize, Deserialize, Clone)]
+pub struct ArchivedApp {
+    pub app: App,
+    pub archived_on: OffsetDateTime,
+}
+
+/// What to do with an application's data when removing it
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum AppDataPolicy {
+    /// Keep the data and archive the application, so it can be restored later
+    Keep,
+
+    /// Delete the application's directory and Docker volumes
+    Purge,
 }
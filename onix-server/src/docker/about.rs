@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use async_graphql::SimpleObject;
 use bollard::Docker;
 
 pub async fn docker_version(docker: &Docker) -> Result<Option<String>> {
@@ -6,3 +7,39 @@ pub async fn docker_version(docker: &Docker) -> Result<Option<String>> {
 
     Ok(version.version)
 }
+
+pub async fn ping_docker(docker: &Docker) -> Result<()> {
+    docker
+        .ping()
+        .await
+        .context("Failed to ping the Docker daemon")?;
+
+    Ok(())
+}
+
+pub async fn docker_daemon_infos(docker: &Docker) -> Result<DockerDaemonInfos> {
+    let version = docker
+        .version()
+        .await
+        .context("Failed to fetch the Docker daemon's version")?;
+
+    let infos = docker
+        .info()
+        .await
+        .context("Failed to fetch the Docker daemon's informations")?;
+
+    Ok(DockerDaemonInfos {
+        version: version.version,
+        api_version: version.api_version,
+        storage_driver: infos.driver,
+        logging_driver: infos.logging_driver,
+    })
+}
+
+#[derive(SimpleObject)]
+pub struct DockerDaemonInfos {
+    pub version: Option<String>,
+    pub api_version: Option<String>,
+    pub storage_driver: Option<String>,
+    pub logging_driver: Option<String>,
+}
@@ -0,0 +1,40 @@
+use anyhow::{bail, Context, Result};
+use bollard::{Docker, API_DEFAULT_VERSION};
+
+/// Name of the Docker host used when none is specified
+pub static DEFAULT_DOCKER_HOST: &str = "local";
+
+/// Timeout for requests sent to the Docker daemon, in seconds
+static REQUEST_TIMEOUT: u64 = 120;
+
+/// Connect to a Docker daemon from its endpoint
+///
+/// Supported formats are 'unix:///path/to/docker.sock', 'tcp://host:port' and 'http://host:port'
+pub fn connect_to_docker(endpoint: &str) -> Result<Docker> {
+    if let Some(path) = endpoint.strip_prefix("unix://") {
+        Docker::connect_with_unix(path, REQUEST_TIMEOUT, API_DEFAULT_VERSION)
+            .with_context(|| format!("Failed to connect to Docker socket at '{path}'"))
+    } else if endpoint.starts_with("tcp://") || endpoint.starts_with("http://") {
+        Docker::connect_with_http(endpoint, REQUEST_TIMEOUT, API_DEFAULT_VERSION)
+            .with_context(|| format!("Failed to connect to Docker daemon at '{endpoint}'"))
+    } else {
+        bail!("Unsupported Docker endpoint '{endpoint}', please use a 'unix://', 'tcp://' or 'http://' URL")
+    }
+}
+
+/// Get the address of the machine a Docker endpoint points to, or `None` for local sockets
+pub fn endpoint_remote_address(endpoint: &str) -> Option<String> {
+    let authority = endpoint
+        .strip_prefix("tcp://")
+        .or_else(|| endpoint.strip_prefix("http://"))?
+        .split('/')
+        .next()?;
+
+    let address = match authority.strip_prefix('[') {
+        // IPv6 address, e.g. '[::1]:2375'
+        Some(rest) => rest.split(']').next()?,
+        None => authority.split(':').next()?,
+    };
+
+    Some(address.to_string())
+}
@@ -6,7 +6,7 @@ use std::{
 use anyhow::{bail, Context, Result};
 use async_graphql::{Enum, InputObject, SimpleObject};
 use bollard::{
-    container::{Config, CreateContainerOptions, ListContainersOptions},
+    container::{Config, CreateContainerOptions, ListContainersOptions, StopContainerOptions},
     models::Mount,
     service::{
         ContainerCreateResponse, ContainerSummary, HostConfig, PortBinding, RestartPolicy,
@@ -18,7 +18,10 @@ use log::info;
 use serde::{Deserialize, Serialize};
 
 use super::images::{has_image_locally, pull_image};
-use super::Port;
+use super::{
+    networks::{connect_to_network, ensure_network},
+    ContainerPrivileges, Port, PortType,
+};
 
 pub async fn create_container(
     docker: &Docker,
@@ -42,8 +45,61 @@ pub async fn create_container(
         port_bindings,
         labels,
         restart_policy,
+        stop_timeout,
+        privileges,
+        networks,
     } = config;
 
+    let mut host_config = HostConfig {
+        restart_policy: Some(RestartPolicy {
+            name: Some(match restart_policy {
+                ContainerRestartPolicy::None => RestartPolicyNameEnum::NO,
+                ContainerRestartPolicy::UnlessStopped => RestartPolicyNameEnum::UNLESS_STOPPED,
+                ContainerRestartPolicy::Always => RestartPolicyNameEnum::ALWAYS,
+            }),
+            maximum_retry_count: None,
+        }),
+
+        port_bindings: Some(
+            port_bindings
+                .iter()
+                .map(|binding| {
+                    (
+                        binding.container_port.to_docker_port(),
+                        Some(vec![PortBinding {
+                            host_ip: None,
+                            host_port: Some(binding.host_port.to_docker_port()),
+                        }]),
+                    )
+                })
+                .collect(),
+        ),
+
+        mounts: Some(
+            mounts
+                .into_iter()
+                .map(
+                    |ContainerMount {
+                         in_host,
+                         in_container,
+                         readonly,
+                     }| Mount {
+                        source: Some(in_host),
+                        target: Some(in_container),
+                        read_only: Some(readonly),
+                        ..Default::default()
+                    },
+                )
+                .collect(),
+        ),
+
+        ..Default::default()
+    };
+
+    privileges
+        .apply_to(&mut host_config)
+        .context("Failed to apply privileges to the container's configuration")?;
+
     let config = Config {
         image: Some(image.clone()),
 
@@ -51,7 +107,13 @@ pub async fn create_container(
 
         env: Some(
             env.iter()
-                .map(|ContainerEnvironmentVar { name, value }| format!("{name}={value}"))
+                .map(
+                    |ContainerEnvironmentVar {
+                         name,
+                         value,
+                         secret: _,
+                     }| format!("{name}={value}"),
+                )
                 .collect(),
         ),
 
@@ -62,61 +124,26 @@ pub async fn create_container(
                 .collect(),
         ),
 
-        host_config: Some(HostConfig {
-            restart_policy: Some(RestartPolicy {
-                name: Some(match restart_policy {
-                    ContainerRestartPolicy::None => RestartPolicyNameEnum::NO,
-                    ContainerRestartPolicy::UnlessStopped => RestartPolicyNameEnum::UNLESS_STOPPED,
-                    ContainerRestartPolicy::Always => RestartPolicyNameEnum::ALWAYS,
-                }),
-                maximum_retry_count: None,
-            }),
-
-            port_bindings: Some(
-                port_bindings
-                    .iter()
-                    .map(|binding| {
-                        (
-                            binding.container_port.to_docker_port(),
-                            Some(vec![PortBinding {
-                                host_ip: None,
-                                host_port: Some(binding.host_port.to_docker_port()),
-                            }]),
-                        )
-                    })
-                    .collect(),
-            ),
-
-            mounts: Some(
-                mounts
-                    .into_iter()
-                    .map(
-                        |ContainerMount {
-                             in_host,
-                             in_container,
-                             readonly,
-                         }| Mount {
-                            source: Some(in_host),
-                            target: Some(in_container),
-                            read_only: Some(readonly),
-                            ..Default::default()
-                        },
-                    )
-                    .collect(),
-            ),
+        host_config: Some(host_config),
 
-            ..Default::default()
-        }),
+        stop_timeout: stop_timeout.map(i64::from),
 
         ..Default::default()
     };
 
     info!("==> Creating container '{name}'...");
 
-    docker
+    let created = docker
         .create_container(Some(CreateContainerOptions { name, platform: None }), config)
         .await
-        .context("Failed to create Docker container")
+        .context("Failed to create Docker container")?;
+
+    for ContainerNetwork { name, aliases } in networks {
+        ensure_network(docker, &name).await?;
+        connect_to_network(docker, &name, &created.id, aliases).await?;
+    }
+
+    Ok(created)
 }
 
 pub struct ContainerCreationConfig {
@@ -127,13 +154,28 @@ pub struct ContainerCreationConfig {
     pub port_bindings: Vec<ContainerPortBinding>,
     pub labels: HashMap<String, String>,
     pub restart_policy: ContainerRestartPolicy,
+    pub stop_timeout: Option<u32>,
+    pub privileges: ContainerPrivileges,
+
+    /// Additional networks to connect the container to, created if needed
+    pub networks: Vec<ContainerNetwork>,
+}
+
+pub struct ContainerNetwork {
+    pub name: String,
+
+    /// Additional hostnames the container can be reached with on this network
+    pub aliases: Vec<String>,
 }
 
-#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone)]
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
 #[graphql(input_name_suffix = "Input")]
 pub struct ContainerEnvironmentVar {
     pub name: String,
     pub value: String,
+
+    /// Encrypt the value at rest and redact it from the API
+    pub secret: Option<bool>,
 }
 
 #[derive(
@@ -206,6 +248,54 @@ pub async fn list_containers(docker: &Docker) -> Result<Vec<ExistingContainer>>
     Ok(list)
 }
 
+/// List the host ports published by all containers, whether they are running or not
+pub async fn list_published_ports(docker: &Docker) -> Result<Vec<Port>> {
+    let containers = list_containers(docker).await?;
+
+    let mut ports = vec![];
+
+    for container in containers {
+        let details = docker
+            .inspect_container(&container.docker_container_id, None)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to inspect Docker container '{}'",
+                    container.docker_container_id
+                )
+            })?;
+
+        let bindings = details
+            .host_config
+            .and_then(|host_config| host_config.port_bindings)
+            .unwrap_or_default();
+
+        for (container_port, bindings) in bindings {
+            let port_type = match container_port.split_once('/') {
+                None | Some((_, "tcp")) => PortType::Tcp,
+                Some((_, "udp")) => PortType::Udp,
+                Some(_) => continue,
+            };
+
+            for binding in bindings.unwrap_or_default() {
+                // Ports without a host port are assigned randomly when the container starts
+                let Some(port) = binding
+                    .host_port
+                    .as_deref()
+                    .and_then(|port| port.split('/').next())
+                    .and_then(|port| port.parse::<u16>().ok())
+                else {
+                    continue;
+                };
+
+                ports.push(Port { port, port_type });
+            }
+        }
+    }
+
+    Ok(ports)
+}
+
 fn decode_container(summary: ContainerSummary) -> Result<ExistingContainer> {
     Ok(ExistingContainer {
         docker_container_id: summary.id.context("Missing ID")?,
@@ -255,9 +345,14 @@ pub async fn start_container(docker: &Docker, container_name: &str) -> Result<()
         .with_context(|| format!("Failed to start container '{container_name}'"))
 }
 
-pub async fn stop_container(docker: &Docker, name: &str) -> Result<()> {
+pub async fn stop_container(docker: &Docker, name: &str, timeout: Option<u32>) -> Result<()> {
     docker
-        .stop_container(name, None)
+        .stop_container(
+            name,
+            timeout.map(|timeout| StopContainerOptions {
+                t: i64::from(timeout),
+            }),
+        )
         .await
         .with_context(|| format!("Failed to stop container '{name}'"))?;
 
@@ -270,3 +365,17 @@ pub async fn remove_container(docker: &Docker, name: &str) -> Result<()> {
         .await
         .with_context(|| format!("Failed to remove container '{name}'"))
 }
+
+pub async fn pause_container(docker: &Docker, name: &str) -> Result<()> {
+    docker
+        .pause_container(name)
+        .await
+        .with_context(|| format!("Failed to pause container '{name}'"))
+}
+
+pub async fn unpause_container(docker: &Docker, name: &str) -> Result<()> {
+    docker
+        .unpause_container(name)
+        .await
+        .with_context(|| format!("Failed to unpause container '{name}'"))
+}
@@ -1,11 +1,19 @@
 mod about;
+mod connect;
 mod constants;
 mod containers;
 mod images;
 mod networking;
+mod networks;
+mod privileges;
+mod volumes;
 
 pub use about::*;
+pub use connect::*;
 pub use constants::*;
 pub use containers::*;
 pub use images::*;
 pub use networking::*;
+pub use networks::*;
+pub use privileges::*;
+pub use volumes::*;
@@ -1,4 +1,7 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    net::{TcpListener, UdpSocket},
+};
 
 use async_graphql::{Enum, InputObject, SimpleObject};
 use serde::{Deserialize, Serialize};
@@ -17,6 +20,19 @@ impl Port {
         self.port == other.port && self.port_type.collides_with(other.port_type)
     }
 
+    /// Check if two ports have the same number and share at least one protocol
+    pub fn overlaps(self, other: Self) -> bool {
+        self.port == other.port
+            && ((self.port_type.covers_tcp() && other.port_type.covers_tcp())
+                || (self.port_type.covers_udp() && other.port_type.covers_udp()))
+    }
+
+    /// Check if the port can currently be bound on the local machine
+    pub fn is_available_locally(self) -> bool {
+        (!self.port_type.covers_tcp() || TcpListener::bind(("0.0.0.0", self.port)).is_ok())
+            && (!self.port_type.covers_udp() || UdpSocket::bind(("0.0.0.0", self.port)).is_ok())
+    }
+
     pub fn to_docker_port(self) -> String {
         format!(
             "{}{}",
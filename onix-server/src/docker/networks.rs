@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use bollard::{
+    network::{
+        ConnectNetworkOptions, CreateNetworkOptions, DisconnectNetworkOptions, ListNetworksOptions,
+    },
+    service::{EndpointSettings, Network},
+    Docker,
+};
+use log::info;
+
+pub async fn find_network(docker: &Docker, name: &str) -> Result<Option<Network>> {
+    let networks = docker
+        .list_networks(Some(ListNetworksOptions {
+            filters: HashMap::from([("name", vec![name])]),
+        }))
+        .await
+        .context("Failed to obtain the list of Docker networks")?;
+
+    // The filter matches on a part of the name, so an exact match must be looked for
+    Ok(networks
+        .into_iter()
+        .find(|network| network.name.as_deref() == Some(name)))
+}
+
+/// Create a bridge network if it doesn't exist yet
+pub async fn ensure_network(docker: &Docker, name: &str) -> Result<()> {
+    if find_network(docker, name).await?.is_some() {
+        return Ok(());
+    }
+
+    info!("==> Creating network '{name}'...");
+
+    docker
+        .create_network(CreateNetworkOptions {
+            name,
+            check_duplicate: true,
+            driver: "bridge",
+            ..Default::default()
+        })
+        .await
+        .with_context(|| format!("Failed to create Docker network '{name}'"))?;
+
+    Ok(())
+}
+
+/// Remove a network if it exists
+pub async fn remove_network(docker: &Docker, name: &str) -> Result<()> {
+    if find_network(docker, name).await?.is_none() {
+        return Ok(());
+    }
+
+    info!("==> Removing network '{name}'...");
+
+    docker
+        .remove_network(name)
+        .await
+        .with_context(|| format!("Failed to remove Docker network '{name}'"))
+}
+
+/// Connect a container to a network (which must exist), unless it is already connected to it
+pub async fn connect_to_network(
+    docker: &Docker,
+    network: &str,
+    container_id: &str,
+    aliases: Vec<String>,
+) -> Result<()> {
+    let existing = docker
+        .inspect_network::<String>(network, None)
+        .await
+        .with_context(|| format!("Failed to inspect Docker network '{network}'"))?;
+
+    if existing
+        .containers
+        .is_some_and(|containers| containers.contains_key(container_id))
+    {
+        return Ok(());
+    }
+
+    docker
+        .connect_network(
+            network,
+            ConnectNetworkOptions {
+                container: container_id,
+                endpoint_config: EndpointSettings {
+                    aliases: Some(aliases),
+                    ..Default::default()
+                },
+            },
+        )
+        .await
+        .with_context(|| format!("Failed to connect container to Docker network '{network}'"))
+}
+
+/// Disconnect a container from a network, unless it is not connected to it
+pub async fn disconnect_from_network(
+    docker: &Docker,
+    network: &str,
+    container_id: &str,
+) -> Result<()> {
+    // Unlike the network's, the container's details include the networks it is connected to while stopped
+    let container = docker
+        .inspect_container(container_id, None)
+        .await
+        .with_context(|| format!("Failed to inspect Docker container '{container_id}'"))?;
+
+    if !container
+        .network_settings
+        .and_then(|settings| settings.networks)
+        .is_some_and(|networks| networks.contains_key(network))
+    {
+        return Ok(());
+    }
+
+    docker
+        .disconnect_network(
+            network,
+            DisconnectNetworkOptions {
+                container: container_id,
+                force: false,
+            },
+        )
+        .await
+        .with_context(|| format!("Failed to disconnect container from Docker network '{network}'"))
+}
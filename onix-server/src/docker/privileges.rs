@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::{Enum, InputObject, SimpleObject};
+use bollard::service::{DeviceMapping, HostConfig, ResourcesUlimits};
+use serde::{Deserialize, Serialize};
+
+/// Elevated permissions and low-level settings for a container
+///
+/// All of these are opt-in, the default value grants no additional privilege
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[graphql(input_name_suffix = "Input")]
+pub struct ContainerPrivileges {
+    /// Kernel capabilities to add (e.g. 'NET_ADMIN')
+    #[serde(default)]
+    pub cap_add: Vec<String>,
+
+    /// Kernel capabilities to drop (e.g. 'MKNOD')
+    #[serde(default)]
+    pub cap_drop: Vec<String>,
+
+    /// Host devices to expose to the container
+    #[serde(default)]
+    pub devices: Vec<ContainerDevice>,
+
+    /// Give extended privileges to the container
+    #[serde(default)]
+    pub privileged: bool,
+
+    /// Security options (e.g. 'no-new-privileges')
+    #[serde(default)]
+    pub security_opt: Vec<String>,
+
+    /// Namespaced kernel parameters
+    #[serde(default)]
+    pub sysctls: Vec<ContainerSysctl>,
+
+    /// Resource limits
+    #[serde(default)]
+    pub ulimits: Vec<ContainerUlimit>,
+
+    /// Size of '/dev/shm', in bytes
+    #[serde(default)]
+    pub shm_size: Option<u64>,
+
+    /// Network mode
+    #[serde(default)]
+    pub network_mode: ContainerNetworkMode,
+}
+
+impl ContainerPrivileges {
+    pub fn validate(&self) -> Result<()> {
+        #[deny(unused_variables)]
+        let Self {
+            cap_add,
+            cap_drop,
+            devices,
+            security_opt,
+            sysctls,
+            ulimits,
+            shm_size,
+
+            privileged: _,
+            network_mode: _,
+        } = self;
+
+        for cap in cap_add.iter().chain(cap_drop) {
+            if cap.is_empty()
+                || !cap
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            {
+                bail!(
+                    "Invalid capability name '{cap}', please use uppercase names like 'NET_ADMIN'"
+                );
+            }
+        }
+
+        if let Some(cap) = cap_add.iter().find(|cap| cap_drop.contains(cap)) {
+            bail!("Capability '{cap}' cannot be both added and dropped");
+        }
+
+        for ContainerDevice {
+            host_path,
+            container_path,
+            cgroup_permissions,
+        } in devices
+        {
+            if host_path.trim().is_empty() || container_path.trim().is_empty() {
+                bail!("Please provide both a host and container path for all devices");
+            }
+
+            if let Some(perms) = cgroup_permissions {
+                if perms.is_empty() || !perms.chars().all(|c| matches!(c, 'r' | 'w' | 'm')) {
+                    bail!("Invalid cgroup permissions '{perms}' for device '{host_path}', only 'r', 'w' and 'm' are allowed");
+                }
+            }
+        }
+
+        if security_opt.iter().any(|opt| opt.trim().is_empty()) {
+            bail!("Please provide non-empty security options");
+        }
+
+        for ContainerSysctl { name, value } in sysctls {
+            if name.trim().is_empty() {
+                bail!("Please provide a non-empty name for all sysctls");
+            }
+
+            if value.trim().is_empty() {
+                bail!("Please provide a value for sysctl '{name}'");
+            }
+        }
+
+        for ContainerUlimit { name, soft, hard } in ulimits {
+            if name.trim().is_empty() {
+                bail!("Please provide a non-empty name for all ulimits");
+            }
+
+            if soft > hard {
+                bail!("Soft limit of ulimit '{name}' cannot be greater than its hard limit");
+            }
+        }
+
+        if let Some(shm_size) = shm_size {
+            if *shm_size == 0 {
+                bail!("Shared memory size must be greater than zero");
+            }
+
+            i64::try_from(*shm_size).context("Shared memory size is too large")?;
+        }
+
+        Ok(())
+    }
+
+    /// List the reasons why the container would run with elevated privileges
+    pub fn elevation_reasons(&self) -> Vec<String> {
+        let mut reasons = vec![];
+
+        if self.privileged {
+            reasons.push("Container is privileged".to_string());
+        }
+
+        for cap in &self.cap_add {
+            reasons.push(format!("Adds capability '{cap}'"));
+        }
+
+        for device in &self.devices {
+            reasons.push(format!("Has access to device '{}'", device.host_path));
+        }
+
+        for opt in &self.security_opt {
+            reasons.push(format!("Uses security option '{opt}'"));
+        }
+
+        for sysctl in &self.sysctls {
+            reasons.push(format!("Sets sysctl '{}'", sysctl.name));
+        }
+
+        if self.network_mode == ContainerNetworkMode::Host {
+            reasons.push("Uses the host's network".to_string());
+        }
+
+        reasons
+    }
+
+    pub fn apply_to(self, host_config: &mut HostConfig) -> Result<()> {
+        #[deny(unused_variables)]
+        let Self {
+            cap_add,
+            cap_drop,
+            devices,
+            privileged,
+            security_opt,
+            sysctls,
+            ulimits,
+            shm_size,
+            network_mode,
+        } = self;
+
+        host_config.cap_add = Some(cap_add);
+        host_config.cap_drop = Some(cap_drop);
+
+        host_config.devices = Some(
+            devices
+                .into_iter()
+                .map(
+                    |ContainerDevice {
+                         host_path,
+                         container_path,
+                         cgroup_permissions,
+                     }| DeviceMapping {
+                        path_on_host: Some(host_path),
+                        path_in_container: Some(container_path),
+                        cgroup_permissions: Some(
+                            cgroup_permissions.unwrap_or_else(|| "rwm".to_string()),
+                        ),
+                    },
+                )
+                .collect(),
+        );
+
+        host_config.privileged = Some(privileged);
+        host_config.security_opt = Some(security_opt);
+
+        host_config.sysctls = Some(
+            sysctls
+                .into_iter()
+                .map(|ContainerSysctl { name, value }| (name, value))
+                .collect::<HashMap<_, _>>(),
+        );
+
+        host_config.ulimits = Some(
+            ulimits
+                .into_iter()
+                .map(|ContainerUlimit { name, soft, hard }| ResourcesUlimits {
+                    name: Some(name),
+                    soft: Some(soft),
+                    hard: Some(hard),
+                })
+                .collect(),
+        );
+
+        host_config.shm_size = shm_size
+            .map(i64::try_from)
+            .transpose()
+            .context("Shared memory size is too large")?;
+
+        host_config.network_mode = match network_mode {
+            ContainerNetworkMode::Default => None,
+            ContainerNetworkMode::Host => Some("host".to_string()),
+        };
+
+        Ok(())
+    }
+}
+
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct ContainerDevice {
+    pub host_path: String,
+    pub container_path: String,
+
+    /// Defaults to 'rwm'
+    pub cgroup_permissions: Option<String>,
+}
+
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct ContainerSysctl {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(SimpleObject, InputObject, Serialize, Deserialize, Clone, PartialEq)]
+#[graphql(input_name_suffix = "Input")]
+pub struct ContainerUlimit {
+    pub name: String,
+    pub soft: i64,
+    pub hard: i64,
+}
+
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainerNetworkMode {
+    /// Use the Docker daemon's default network
+    #[default]
+    Default,
+
+    /// Share the host's network stack
+    Host,
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use bollard::{
+    container::{Config, CreateContainerOptions, RemoveContainerOptions},
+    errors::Error as DockerError,
+    models::{Mount, MountTypeEnum},
+    service::HostConfig,
+    volume::RemoveVolumeOptions,
+    Docker,
+};
+use futures::TryStreamExt;
+use log::info;
+use rand::Rng;
+
+use super::{
+    constants::NAME_PREFIX,
+    images::{has_image_locally, pull_image},
+};
+
+/// Image used to run the temporary containers copying volumes
+static VOLUME_COPY_IMAGE: &str = "alpine:3";
+
+/// Copy the content of a Docker named volume into another one (which is created if needed)
+pub async fn copy_volume(docker: &Docker, from: &str, to: &str) -> Result<()> {
+    if !has_image_locally(docker, VOLUME_COPY_IMAGE).await? {
+        info!("==> Pulling image '{VOLUME_COPY_IMAGE}' to copy volumes...");
+
+        pull_image(docker, VOLUME_COPY_IMAGE).await?;
+    }
+
+    let name = format!(
+        "{NAME_PREFIX}volume_copy_{}",
+        base62::encode(rand::thread_rng().gen::<u64>())
+    );
+
+    let volume_mount = |source: &str, target: &str, read_only: bool| Mount {
+        source: Some(source.to_string()),
+        target: Some(target.to_string()),
+        typ: Some(MountTypeEnum::VOLUME),
+        read_only: Some(read_only),
+        ..Default::default()
+    };
+
+    let config = Config {
+        image: Some(VOLUME_COPY_IMAGE.to_string()),
+        cmd: Some(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "cp -a /from/. /to/".to_string(),
+        ]),
+        host_config: Some(HostConfig {
+            mounts: Some(vec![
+                volume_mount(from, "/from", true),
+                volume_mount(to, "/to", false),
+            ]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    info!("==> Copying volume '{from}' to '{to}'...");
+
+    docker
+        .create_container(
+            Some(CreateContainerOptions {
+                name: name.as_str(),
+                platform: None,
+            }),
+            config,
+        )
+        .await
+        .context("Failed to create the volume copy container")?;
+
+    let copied: Result<()> = async {
+        docker
+            .start_container::<String>(&name, None)
+            .await
+            .context("Failed to start the volume copy container")?;
+
+        docker
+            .wait_container::<String>(&name, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .context("Failed to copy the volume's content")?;
+
+        Ok(())
+    }
+    .await;
+
+    docker
+        .remove_container(
+            &name,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await
+        .context("Failed to remove the volume copy container")?;
+
+    copied.with_context(|| format!("Failed to copy volume '{from}' to '{to}'"))
+}
+
+/// Remove a Docker named volume, which must not be used by any container
+///
+/// Returns `false` if the volume did not exist.
+pub async fn remove_volume(docker: &Docker, name: &str) -> Result<bool> {
+    info!("==> Removing volume '{name}'...");
+
+    match docker
+        .remove_volume(name, Some(RemoveVolumeOptions { force: false }))
+        .await
+    {
+        Ok(()) => Ok(true),
+        Err(DockerError::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(false),
+        Err(err) => Err(err).with_context(|| format!("Failed to remove Docker volume '{name}'")),
+    }
+}
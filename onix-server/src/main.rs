@@ -2,14 +2,17 @@
 #![forbid(unused_must_use)]
 
 use onix_server::{
-    apps::AppRunnerConfig,
+    apps::{AppRunnerConfig, SecretsKey},
     cmd::Cmd,
+    docker::{connect_to_docker, endpoint_remote_address, DEFAULT_DOCKER_HOST},
     saving::{save_user_data, try_load_user_data},
-    server::{self, StateConfig},
+    server::{self, DockerHost, DockerHostConfig, StateConfig},
     utils::time::get_now,
 };
 
-use anyhow::{Context, Result};
+use std::{fs, sync::Arc};
+
+use anyhow::{bail, Context, Result};
 use bollard::Docker;
 use clap::Parser;
 use log::{info, LevelFilter};
@@ -33,20 +36,73 @@ async fn main() {
 }
 
 async fn inner_main(cmd: Cmd) -> Result<()> {
-    let docker = Docker::connect_with_socket_defaults().context("Failed to connect to Docker")?;
+    let docker_hosts = if cmd.docker_hosts.is_empty() {
+        vec![DockerHostConfig {
+            name: DEFAULT_DOCKER_HOST.to_string(),
+            connector: Arc::new(|| {
+                Docker::connect_with_socket_defaults().context("Failed to connect to Docker")
+            }),
+            remote_address: None,
+        }]
+    } else {
+        cmd.docker_hosts
+            .iter()
+            .map(|host| parse_docker_host(host))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    for (i, host) in docker_hosts.iter().enumerate() {
+        if docker_hosts[..i]
+            .iter()
+            .any(|other| other.name == host.name)
+        {
+            bail!("Docker host '{}' was declared twice", host.name);
+        }
+    }
+
+    let default_docker_host = match cmd.default_docker_host {
+        Some(name) => name,
+        None => docker_hosts[0].name.clone(),
+    };
+
+    if !docker_hosts
+        .iter()
+        .any(|host| host.name == default_docker_host)
+    {
+        bail!("Default Docker host '{default_docker_host}' was not declared");
+    }
+
+    let docker_hosts = docker_hosts
+        .into_iter()
+        .map(DockerHost::connect)
+        .collect::<Result<Vec<_>>>()?;
 
     let data_dir = dirs::data_local_dir()
         .context("Failed to obtain path to local data directory")?
         .join("onix");
 
+    let secrets_key = match cmd.secrets_passphrase_file {
+        Some(path) => {
+            let passphrase =
+                fs::read_to_string(&path).context("Failed to read secrets passphrase file")?;
+
+            SecretsKey::from_passphrase(passphrase.trim_end_matches(['\r', '\n']), &data_dir)?
+        }
+
+        None => SecretsKey::load_or_generate(&data_dir)?,
+    };
+
     let config = StateConfig {
         address: cmd.address.unwrap_or_else(|| "127.0.0.1".into()),
         port: cmd.port.unwrap_or(5871),
-        docker,
+        public_hostname: cmd.public_hostname,
+        docker_hosts,
+        default_docker_host,
 
         // TODO: configurable directories
         runner_config: AppRunnerConfig {
             data_dir: data_dir.clone(),
+            secrets_key,
         },
 
         // TODO: load user data from disk
@@ -57,3 +113,21 @@ async fn inner_main(cmd: Cmd) -> Result<()> {
 
     server::start(config).await
 }
+
+fn parse_docker_host(input: &str) -> Result<DockerHostConfig> {
+    let (name, endpoint) = input
+        .split_once('=')
+        .with_context(|| format!("Invalid Docker host '{input}', expected 'name=endpoint'"))?;
+
+    if name.trim().is_empty() {
+        bail!("Please provide a non-empty name for Docker host '{input}'");
+    }
+
+    let endpoint = endpoint.to_string();
+
+    Ok(DockerHostConfig {
+        name: name.to_string(),
+        remote_address: endpoint_remote_address(&endpoint),
+        connector: Arc::new(move || connect_to_docker(&endpoint)),
+    })
+}
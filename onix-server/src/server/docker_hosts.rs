@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_graphql::{Enum, SimpleObject};
+use bollard::Docker;
+
+use crate::apps::AppRunningStatus;
+
+use super::docker_supervisor::DockerConnectionState;
+
+/// Function used to (re-)create a Docker API client
+pub type DockerConnector = Arc<dyn Fn() -> Result<Docker> + Send + Sync>;
+
+/// Configuration of a named Docker host
+pub struct DockerHostConfig {
+    pub name: String,
+    pub connector: DockerConnector,
+    pub remote_address: Option<String>,
+}
+
+/// A named Docker host the server can manage applications on
+#[derive(Clone)]
+pub struct DockerHost {
+    /// Name of the host
+    pub name: String,
+
+    /// Docker API client
+    pub client: Docker,
+
+    /// Function used to reconnect to the Docker daemon
+    pub connector: DockerConnector,
+
+    /// Connection state of the Docker daemon
+    pub connection: DockerConnectionState,
+
+    /// Address of the machine the Docker daemon runs on, or `None` for the local machine
+    pub remote_address: Option<String>,
+}
+
+impl DockerHost {
+    pub fn connect(config: DockerHostConfig) -> Result<Self> {
+        #[deny(unused_variables)]
+        let DockerHostConfig {
+            name,
+            connector,
+            remote_address,
+        } = config;
+
+        Ok(Self {
+            client: connector()?,
+            name,
+            connector,
+            connection: DockerConnectionState::default(),
+            remote_address,
+        })
+    }
+}
+
+/// Aggregated status of the applications of a Docker host
+#[derive(SimpleObject)]
+pub struct DockerHostSummary {
+    pub name: String,
+    pub is_default: bool,
+    pub connection: DockerConnectionState,
+    pub apps_count: usize,
+    pub apps_status: Vec<AppsStatusCount>,
+}
+
+#[derive(SimpleObject)]
+pub struct AppsStatusCount {
+    pub status: AppsStatusCategory,
+    pub count: usize,
+}
+
+/// Status of an application, or 'Unknown' if it couldn't be fetched
+#[derive(Enum, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppsStatusCategory {
+    NotCreated,
+    PartiallyCreated,
+    Zombie,
+    Intermediary,
+    Stopped,
+    PartiallyRunning,
+    FullyRunning,
+    Unknown,
+}
+
+impl From<AppRunningStatus> for AppsStatusCategory {
+    fn from(status: AppRunningStatus) -> Self {
+        match status {
+            AppRunningStatus::NotCreated => Self::NotCreated,
+            AppRunningStatus::PartiallyCreated => Self::PartiallyCreated,
+            AppRunningStatus::Zombie => Self::Zombie,
+            AppRunningStatus::Intermediary => Self::Intermediary,
+            AppRunningStatus::Stopped => Self::Stopped,
+            AppRunningStatus::PartiallyRunning => Self::PartiallyRunning,
+            AppRunningStatus::FullyRunning => Self::FullyRunning,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use async_graphql::SimpleObject;
+use log::{debug, error, info, warn};
+use time::OffsetDateTime;
+use tokio::time::sleep;
+
+use crate::{
+    docker::{self, DockerDaemonInfos},
+    utils::time::get_now,
+};
+
+use super::{
+    docker_hosts::DockerHost,
+    state::{State, WrappedState},
+};
+
+/// Connection state of the Docker daemon, as seen by the supervisor
+#[derive(SimpleObject, Clone)]
+pub struct DockerConnectionState {
+    /// Indicates if the last check succeeded
+    pub connected: bool,
+
+    /// Date of the last check
+    pub last_check_on: Option<OffsetDateTime>,
+
+    /// Last connection error that occurred
+    pub last_error: Option<String>,
+
+    /// Date of the last connection error
+    pub last_error_on: Option<OffsetDateTime>,
+
+    /// Number of failed reconnection attempts since the connection was lost
+    pub failed_attempts: u32,
+}
+
+impl Default for DockerConnectionState {
+    fn default() -> Self {
+        Self {
+            connected: true,
+            last_check_on: None,
+            last_error: None,
+            last_error_on: None,
+            failed_attempts: 0,
+        }
+    }
+}
+
+impl DockerConnectionState {
+    fn mark_connected(&mut self) {
+        self.connected = true;
+        self.last_check_on = Some(get_now());
+        self.failed_attempts = 0;
+    }
+
+    fn mark_failed(&mut self, err: &anyhow::Error) {
+        let now = get_now();
+
+        self.connected = false;
+        self.last_check_on = Some(now);
+        self.last_error = Some(format!("{err:?}"));
+        self.last_error_on = Some(now);
+        self.failed_attempts += 1;
+    }
+}
+
+/// Health report of the Docker daemon
+#[derive(SimpleObject)]
+pub struct DockerHealth {
+    /// Connection state of the daemon
+    pub connection: DockerConnectionState,
+
+    /// Informations about the daemon, if it could be fetched
+    pub daemon: Option<DockerDaemonInfos>,
+
+    /// Error that occurred while fetching the daemon's informations
+    pub daemon_error: Option<String>,
+}
+
+static LOGGER_TARGET: &str = "docker-supervisor";
+static CHECK_INTERVAL: Duration = Duration::from_secs(5);
+static MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+static MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// A loop checking a Docker host's daemon is reachable, reconnecting with backoff when it's not
+pub async fn docker_supervisor(state: WrappedState, host_name: String) -> ! {
+    let mut reconnect_delay = MIN_RECONNECT_DELAY;
+
+    loop {
+        // Clone the client to avoid holding the state's lock during the check
+        let docker = get_host(&mut *state.lock().await, &host_name)
+            .client
+            .clone();
+
+        match docker::ping_docker(&docker).await {
+            Ok(()) => {
+                let mut state = state.lock().await;
+                let host = get_host(&mut state, &host_name);
+
+                if !host.connection.connected {
+                    info!(target: LOGGER_TARGET, "Connection to Docker host '{host_name}' was restored.");
+                }
+
+                host.connection.mark_connected();
+
+                drop(state);
+
+                reconnect_delay = MIN_RECONNECT_DELAY;
+
+                sleep(CHECK_INTERVAL).await;
+            }
+
+            Err(err) => {
+                {
+                    let mut state = state.lock().await;
+                    let host = get_host(&mut state, &host_name);
+
+                    if host.connection.connected {
+                        error!(target: LOGGER_TARGET, "Lost connection to Docker host '{host_name}': {err:?}");
+                    } else {
+                        debug!(target: LOGGER_TARGET, "| Docker host '{host_name}' is still unreachable: {err:?}");
+                    }
+
+                    host.connection.mark_failed(&err);
+                }
+
+                warn!(
+                    target: LOGGER_TARGET,
+                    "Reconnecting to Docker host '{host_name}' in {} second(s)...",
+                    reconnect_delay.as_secs()
+                );
+
+                sleep(reconnect_delay).await;
+
+                reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+
+                let mut state = state.lock().await;
+                let host = get_host(&mut state, &host_name);
+
+                match (host.connector)() {
+                    Ok(docker) => host.client = docker,
+                    Err(err) => {
+                        error!(target: LOGGER_TARGET, "Failed to create a new Docker client for host '{host_name}': {err:?}");
+                        host.connection.mark_failed(&err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn get_host<'a>(state: &'a mut State, host_name: &str) -> &'a mut DockerHost {
+    state
+        .docker_host_mut(host_name)
+        .expect("Assertion error: supervised Docker host was not found in state")
+}
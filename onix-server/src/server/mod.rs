@@ -1,7 +1,10 @@
+mod docker_hosts;
+mod docker_supervisor;
 mod graphql;
 mod logger;
 mod mutations;
 mod queries;
+mod reconciler;
 mod state;
 mod user_data;
 
@@ -11,13 +14,16 @@ use axum::{extract::Extension, http::Method, routing::get, Router, Server};
 use log::info;
 use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
 
+pub use docker_hosts::{DockerConnector, DockerHost, DockerHostConfig};
 pub use state::StateConfig;
 
 use crate::server::{
+    docker_supervisor::docker_supervisor,
     graphql::{graphiql, graphql_handler, AppSchema},
     logger::Logger,
     mutations::MutationRoot,
     queries::QueryRoot,
+    reconciler::reconciler,
     state::WrappedState,
     user_data::user_data_saver,
 };
@@ -59,6 +65,34 @@ pub async fn start(config: StateConfig) -> Result<()> {
         panic!("Assertion error: user data saver loop unexpectedly exited!");
     });
 
+    info!("Starting the reconciliation thread...");
+
+    let state_for_reconciler = state.clone();
+
+    tokio::spawn(async move {
+        reconciler(state_for_reconciler).await;
+        panic!("Assertion error: reconciliation loop unexpectedly exited!");
+    });
+
+    let host_names = state
+        .lock()
+        .await
+        .docker_hosts
+        .iter()
+        .map(|host| host.name.clone())
+        .collect::<Vec<_>>();
+
+    for host_name in host_names {
+        info!("Starting the Docker supervisor thread for host '{host_name}'...");
+
+        let state_for_supervisor = state.clone();
+
+        tokio::spawn(async move {
+            docker_supervisor(state_for_supervisor, host_name).await;
+            panic!("Assertion error: Docker supervisor loop unexpectedly exited!");
+        });
+    }
+
     info!("Starting the server on {addr}...");
 
     Server::bind(&addr)
@@ -1,97 +1,967 @@
 use anyhow::Context as _;
 use async_graphql::{Context, Object};
+use log::{error, info};
 
 use crate::{
-    apps::{App, AppId, AppTemplate},
-    utils::graphql::{Result, Void},
+    apps::{
+        check_app_dependencies, check_desired_states, decode_template_params, find_global_path,
+        parse_template_version, resolve_template, seal_param_values, sorted_app_dependencies,
+        sorted_app_dependents, App, AppDependency, AppDesiredState, AppId, AppRunner, AppTemplate,
+        AppTemplateParamValue, AppTemplateSource, AppVolumeStorage, ContainerActionReport,
+        GlobalPath,
+    },
+    data::{AppDataPolicy, ArchivedApp},
+    docker::list_published_ports,
+    stores::{StoreConfig, StoreInterface},
+    utils::{
+        graphql::{Result, Void},
+        time::get_now,
+    },
 };
 
-use super::state::{get_runner_for, get_state};
+use super::state::{
+    get_runner_for, get_state, start_app_with_dependencies, stop_running_dependents,
+    sync_desired_state, sync_started_desired_state, State,
+};
 
 pub struct MutationRoot;
 
 #[Object]
 impl MutationRoot {
-    async fn create_app(&self, ctx: &Context<'_>, input: AppTemplate) -> Result<App> {
-        let mut state = get_state(ctx).await;
+    async fn create_app(
+        &self,
+        ctx: &Context<'_>,
+        input: AppTemplate,
+        host: Option<String>,
+    ) -> Result<App> {
+        insert_new_app(&mut *get_state(ctx).await, input, host, None)
+    }
+
+    async fn create_app_from_template(
+        &self,
+        ctx: &Context<'_>,
+        template: String,
+        params: Vec<AppTemplateParamValue>,
+        host: Option<String>,
+        store_url: Option<String>,
+    ) -> Result<App> {
+        let state = &mut *get_state(ctx).await;
+
+        let input = resolve_template(&template, params.clone())
+            .context("Failed to resolve the template's parameters")?;
+
+        let source = AppTemplateSource {
+            store_url,
+            params: seal_param_values(&template, params, state.runner_env.secrets_key())?,
+        };
+
+        insert_new_app(state, input, host, Some(source))
+    }
+
+    async fn update_app(&self, ctx: &Context<'_>, id: AppId, template: AppTemplate) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        let (updated, diff) = runner
+            .app()
+            .update(template, state.runner_env.secrets_key())
+            .context("Failed to update the application")?;
+
+        updated.check_global_paths(&state.user_data().global_paths)?;
+
+        info!(
+            "Updating application '{}': {} added, {} removed, {} changed, {} unchanged container(s)",
+            updated.name,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len(),
+            diff.unchanged.len()
+        );
+
+        runner.apply_update(&updated, &diff).await?;
+
         let apps = &mut state.user_data_mut().apps;
 
-        if apps.iter().any(|app| app.name == input.name) {
+        let index = apps
+            .iter()
+            .position(|app| app.id == id)
+            .expect("Assertion error: application was not found in user data after update");
+
+        apps[index] = updated.clone();
+
+        Ok(updated)
+    }
+
+    /// Upgrade an application to the latest version of the store template it was created from
+    ///
+    /// Parameters are the ones provided when the application was created, plus the provided ones.
+    async fn upgrade_app(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        params: Option<Vec<AppTemplateParamValue>>,
+    ) -> Result<App> {
+        let origin = get_state(ctx)
+            .await
+            .user_data()
+            .apps
+            .iter()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?
+            .template_origin
+            .clone()
+            .ok_or("Application was not created from a template")?;
+
+        let store_url = origin
+            .store_url
+            .clone()
+            .ok_or("Application was not created from a store's template")?;
+
+        let store = StoreInterface::new(StoreConfig {
+            url: store_url.clone(),
+        })
+        .pull()
+        .await
+        .context("Failed to pull the application's store")?;
+
+        let template = store.find_template(&origin.template_id).with_context(|| {
+            format!(
+                "Template '{}' was not found in the store",
+                origin.template_id
+            )
+        })?;
+
+        let latest_version = template
+            .template_version()
+            .context("Store's template has no version")?;
+
+        if parse_template_version(latest_version)? <= parse_template_version(&origin.version)? {
+            Err("Application is already using the latest version of its template")?;
+        }
+
+        let template = template.to_yaml()?;
+
+        let state = &mut get_state(ctx).await;
+
+        // The application may have been upgraded, modified or removed while pulling the store
+        let current_origin = state
+            .user_data()
+            .apps
+            .iter()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?
+            .template_origin
+            .as_ref();
+
+        if current_origin != Some(&origin) {
+            Err("Application was modified while pulling its store, please try again")?;
+        }
+
+        let key = state.runner_env.secrets_key();
+
+        let mut values = origin
+            .params
+            .into_iter()
+            .map(|AppTemplateParamValue { name, value }| {
+                Ok(AppTemplateParamValue {
+                    value: key.reveal(&value)?,
+                    name,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Failed to decrypt the application's parameters")?;
+
+        for value in params.unwrap_or_default() {
+            values.retain(|other| other.name != value.name);
+            values.push(value);
+        }
+
+        let declared = decode_template_params(&template)?;
+        values.retain(|value| declared.iter().any(|param| param.name == value.name));
+
+        let input = resolve_template(&template, values.clone())
+            .context("Failed to resolve the template's parameters")?;
+
+        let source = AppTemplateSource {
+            store_url: Some(store_url),
+            params: seal_param_values(&template, values, key)?,
+        };
+
+        let runner = get_runner_for(state, id).await?;
+
+        let (updated, diff) = runner
+            .app()
+            .upgrade(input, source, key)
+            .context("Failed to upgrade the application")?;
+
+        updated.check_global_paths(&state.user_data().global_paths)?;
+
+        info!(
+            "Upgrading application '{}' to template version {}: {} added, {} removed, {} changed, {} unchanged container(s)",
+            updated.name,
+            latest_version,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len(),
+            diff.unchanged.len()
+        );
+
+        runner.apply_update(&updated, &diff).await?;
+
+        let apps = &mut state.user_data_mut().apps;
+
+        let index = apps
+            .iter()
+            .position(|app| app.id == id)
+            .expect("Assertion error: application was not found in user data after upgrade");
+
+        apps[index] = updated.clone();
+
+        Ok(updated)
+    }
+
+    /// Create a copy of an application, with its host ports remapped to ones free on its Docker host
+    ///
+    /// The application must be stopped to copy its data.
+    async fn clone_app(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        new_name: String,
+        copy_data: bool,
+    ) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        if state
+            .user_data()
+            .apps
+            .iter()
+            .any(|app| app.name == new_name)
+        {
             Err("An application already exists with the provided name")?;
         }
 
-        let app = App::new(input).context("Failed to create the application")?;
+        let runner = get_runner_for(state, id).await?;
+        let host = &runner.app().host;
 
-        apps.push(app.clone());
+        let docker_host = state
+            .docker_host(host)
+            .expect("Assertion error: runner was created for an unknown Docker host");
 
-        Ok(app)
+        // Ports published by containers (even stopped ones), and the ones of applications not created yet
+        let mut used_ports = list_published_ports(&docker_host.client).await?;
+
+        used_ports.extend(
+            state
+                .user_data()
+                .apps
+                .iter()
+                .filter(|app| &app.host == host)
+                .flat_map(|app| &app.containers)
+                .flat_map(|container| &container.port_bindings)
+                .map(|binding| binding.host_port),
+        );
+
+        // Ports of the local machine are also probed, in case other programs use them
+        let is_local = docker_host.remote_address.is_none();
+
+        let clone = runner
+            .app()
+            .duplicate(new_name, |port| {
+                !used_ports.iter().any(|used| used.overlaps(port))
+                    && (!is_local || port.is_available_locally())
+            })
+            .context("Failed to clone the application")?;
+
+        if copy_data {
+            if let Err(err) = runner.copy_data_to(&clone).await {
+                // Data copied so far doesn't belong to any application
+                if let Err(err) = runner.for_app(&clone).purge_data().await {
+                    error!(
+                        "Failed to clean up the data copied for clone '{}': {err:?}",
+                        clone.name
+                    );
+                }
+
+                Err(err.context("Failed to copy the application's data"))?;
+            }
+        }
+
+        state.user_data_mut().apps.push(clone.clone());
+
+        Ok(clone)
+    }
+
+    /// Rename an application, moving its data and re-creating its existing containers
+    async fn rename_app(&self, ctx: &Context<'_>, id: AppId, new_name: String) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        let (renamed, diff) = runner
+            .app()
+            .renamed(new_name, &state.user_data().apps)
+            .context("Failed to rename the application")?;
+
+        runner.apply_rename(&renamed, &diff).await?;
+
+        let apps = &mut state.user_data_mut().apps;
+
+        let index = apps
+            .iter()
+            .position(|app| app.id == id)
+            .expect("Assertion error: application was not found in user data after renaming");
+
+        apps[index] = renamed.clone();
+
+        Ok(renamed)
+    }
+
+    /// Rename an application's container, moving its data and re-creating it if it exists
+    async fn rename_app_container(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        container: String,
+        new_name: String,
+    ) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        let (renamed, diff) = runner
+            .app()
+            .with_renamed_container(&container, new_name)
+            .context("Failed to rename the container")?;
+
+        runner.apply_rename(&renamed, &diff).await?;
+
+        let apps = &mut state.user_data_mut().apps;
+
+        let index = apps
+            .iter()
+            .position(|app| app.id == id)
+            .expect("Assertion error: application was not found in user data after renaming");
+
+        apps[index] = renamed.clone();
+
+        Ok(renamed)
+    }
+
+    /// Replace an application's tags
+    async fn set_app_tags(&self, ctx: &Context<'_>, id: AppId, tags: Vec<String>) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let mut user_data = state.user_data_mut();
+
+        let app = user_data
+            .apps
+            .iter_mut()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?;
+
+        app.set_tags(tags)?;
+
+        Ok(app.clone())
+    }
+
+    /// Move an application to a group, or remove it from its group
+    async fn set_app_group(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        group: Option<String>,
+    ) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let mut user_data = state.user_data_mut();
+
+        let app = user_data
+            .apps
+            .iter_mut()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?;
+
+        app.set_group(group)?;
+
+        Ok(app.clone())
+    }
+
+    /// Set the state the reconciliation loop must keep an application in
+    ///
+    /// Applications without a desired state are not managed by the loop.
+    /// An application cannot be desired to be running while one of its dependencies is desired not to be.
+    async fn set_app_desired_state(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        desired_state: Option<AppDesiredState>,
+    ) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let mut apps = state.user_data().apps.clone();
+
+        let app = apps
+            .iter_mut()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?;
+
+        app.desired_state = desired_state;
+
+        let updated = app.clone();
+
+        check_desired_states(&apps).context("Conflicting desired states")?;
+
+        let apps = &mut state.user_data_mut().apps;
+
+        let index = apps.iter().position(|app| app.id == id).expect(
+            "Assertion error: application was not found in user data after checking desired states",
+        );
+
+        apps[index] = updated.clone();
+
+        Ok(updated)
+    }
+
+    /// Replace the applications an application depends on
+    ///
+    /// Containers must be re-created for changes to shared networks to apply.
+    async fn set_app_dependencies(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        dependencies: Vec<AppDependency>,
+    ) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let mut apps = state.user_data().apps.clone();
+
+        let app = apps
+            .iter_mut()
+            .find(|app| app.id == id)
+            .ok_or("Provided application ID was not found")?;
+
+        let previous = app.clone();
+
+        app.dependencies = dependencies;
+
+        let updated = app.clone();
+
+        check_app_dependencies(&apps).context("Invalid dependencies provided")?;
+        check_desired_states(&apps).context("Conflicting desired states")?;
+
+        // Existing containers are only connected to their dependencies' networks when created
+        for dep in &updated.dependencies {
+            let was_shared = previous
+                .dependencies
+                .iter()
+                .any(|other| other.app_id == dep.app_id && other.shared_network == Some(true));
+
+            if dep.shared_network == Some(true) && !was_shared {
+                get_runner_for(state, dep.app_id)
+                    .await?
+                    .expose_on_shared_network()
+                    .await?;
+            }
+        }
+
+        get_runner_for(state, id)
+            .await?
+            .for_app(&updated)
+            .update_dependencies_networks(&previous)
+            .await?;
+
+        let apps = &mut state.user_data_mut().apps;
+
+        let index = apps.iter().position(|app| app.id == id).expect(
+            "Assertion error: application was not found in user data after checking dependencies",
+        );
+
+        apps[index] = updated.clone();
+
+        Ok(updated)
+    }
+
+    /// Register a global path, or replace the existing one with the same identifier
+    ///
+    /// Containers using a replaced global path must be re-created to use the new host path.
+    async fn set_global_path(
+        &self,
+        ctx: &Context<'_>,
+        global_path: GlobalPath,
+    ) -> Result<GlobalPath> {
+        global_path
+            .validate()
+            .context("Invalid global path provided")?;
+
+        let state = &mut get_state(ctx).await;
+        let global_paths = &mut state.user_data_mut().global_paths;
+
+        match global_paths
+            .iter_mut()
+            .find(|path| path.id == global_path.id)
+        {
+            Some(existing) => *existing = global_path.clone(),
+            None => global_paths.push(global_path.clone()),
+        }
+
+        Ok(global_path)
+    }
+
+    /// Remove a global path which isn't used by any application
+    async fn remove_global_path(&self, ctx: &Context<'_>, id: String) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
+
+        if find_global_path(&state.user_data().global_paths, &id).is_none() {
+            Err("Provided global path ID was not found")?;
+        }
+
+        if let Some(app) = state
+            .user_data()
+            .apps
+            .iter()
+            .find(|app| app.used_global_paths().any(|used| used == id))
+        {
+            Err(format!(
+                "Global path '{id}' is still used by application '{}'",
+                app.name
+            ))?;
+        }
+
+        state
+            .user_data_mut()
+            .global_paths
+            .retain(|path| path.id != id);
+
+        Ok(Void)
     }
 
     async fn create_app_containers(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
-        let state = &get_state(ctx).await;
+        let state = &mut get_state(ctx).await;
 
         let runner = get_runner_for(state, id).await?;
 
-        runner
-            .create_containers()
-            .await
-            .map(Into::into)
-            .map_err(Into::into)
+        let was_absent = runner.app().desired_state == Some(AppDesiredState::Absent);
+
+        runner.create_containers().await?;
+
+        // Applications desired to be running get their new containers started by the loop
+        if was_absent {
+            sync_desired_state(state, &[id], AppDesiredState::Stopped);
+        }
+
+        Ok(Void)
     }
 
+    /// Start an application, after starting the applications it depends on
     async fn start_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
-        let state = &get_state(ctx).await;
+        let state = &mut get_state(ctx).await;
+
+        let started = start_app_with_dependencies(state, id).await?;
+
+        sync_started_desired_state(state, &started);
+
+        Ok(Void)
+    }
+
+    /// Stop an application
+    ///
+    /// Fails if applications depending on it are running or desired to be, unless cascading which stops them first.
+    async fn stop_app(&self, ctx: &Context<'_>, id: AppId, cascade: Option<bool>) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
+        let cascade = cascade == Some(true);
+
+        let desired_running = sorted_app_dependents(&state.user_data().apps, id)?
+            .into_iter()
+            .filter(|app| app.desired_state == Some(AppDesiredState::Running))
+            .collect::<Vec<_>>();
+
+        if !desired_running.is_empty() && !cascade {
+            Err(format!(
+                "Application is required by application(s) desired to be running: {}",
+                desired_running
+                    .iter()
+                    .map(|app| format!("'{}'", app.name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))?;
+        }
+
+        let desired_running = desired_running
+            .into_iter()
+            .map(|app| app.id)
+            .collect::<Vec<_>>();
 
         let runner = get_runner_for(state, id).await?;
 
-        runner.start().await.map(Into::into).map_err(Into::into)
+        let mut stopped = stop_running_dependents(state, id, cascade).await?;
+
+        runner.stop().await?;
+
+        stopped.push(id);
+        stopped.extend(desired_running);
+        sync_desired_state(state, &stopped, AppDesiredState::Stopped);
+
+        Ok(Void)
     }
 
-    async fn stop_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
-        let state = &get_state(ctx).await;
+    /// Restart an application
+    ///
+    /// The applications it depends on are expected to be running.
+    async fn restart_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
 
         let runner = get_runner_for(state, id).await?;
 
-        runner.stop().await.map(Into::into).map_err(Into::into)
+        runner.restart().await?;
+
+        let mut running = sorted_app_dependencies(&state.user_data().apps, id)?
+            .into_iter()
+            .map(|app| app.id)
+            .collect::<Vec<_>>();
+
+        running.push(id);
+        sync_started_desired_state(state, &running);
+
+        Ok(Void)
     }
 
-    async fn remove_app_containers(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+    async fn pause_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
         let state = &get_state(ctx).await;
 
         let runner = get_runner_for(state, id).await?;
 
-        runner
-            .remove_containers()
-            .await
-            .map(Into::into)
-            .map_err(Into::into)
+        runner.pause().await.map(Into::into).map_err(Into::into)
     }
 
-    async fn destroy_app_containers(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+    async fn unpause_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
         let state = &get_state(ctx).await;
 
         let runner = get_runner_for(state, id).await?;
 
-        runner
-            .remove_containers()
-            .await
-            .map(Into::into)
-            .map_err(Into::into)
+        runner.unpause().await.map(Into::into).map_err(Into::into)
     }
 
-    async fn remove_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+    /// The application stops being managed by the reconciliation loop, if it was
+    async fn start_app_container(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        container: String,
+    ) -> Result<ContainerActionReport> {
         let state = &mut get_state(ctx).await;
 
-        get_runner_for(state, id)
-            .await?
-            .ensure_can_be_removed()
+        let runner = get_runner_for(state, id).await?;
+
+        let report = runner.start_app_container(&container).await?;
+
+        suspend_desired_state(state, id);
+
+        Ok(report)
+    }
+
+    /// The application stops being managed by the reconciliation loop, if it was
+    async fn stop_app_container(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        container: String,
+        stop_dependents: Option<bool>,
+    ) -> Result<ContainerActionReport> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        let report = runner
+            .stop_app_container(&container, stop_dependents.unwrap_or(false))
             .await?;
 
-        let apps = &mut state.user_data_mut().apps;
+        suspend_desired_state(state, id);
+
+        Ok(report)
+    }
+
+    /// The application stops being managed by the reconciliation loop, if it was
+    async fn restart_app_container(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        container: String,
+        restart_dependents: Option<bool>,
+    ) -> Result<ContainerActionReport> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        let report = runner
+            .restart_app_container(&container, restart_dependents.unwrap_or(false))
+            .await?;
+
+        suspend_desired_state(state, id);
+
+        Ok(report)
+    }
+
+    /// Re-apply the declared ownership on the directories of an application's external volumes
+    ///
+    /// Returns the paths of the directories that were repaired.
+    async fn repair_app_volumes_ownership(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+    ) -> Result<Vec<String>> {
+        let state = &get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        runner.repair_volumes_ownership().map_err(Into::into)
+    }
+
+    async fn remove_app_containers(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        runner.remove_containers().await?;
+
+        sync_desired_state(state, &[id], AppDesiredState::Absent);
+
+        Ok(Void)
+    }
+
+    /// Remove an application's containers and its Docker named volumes
+    ///
+    /// When volumes holding non-disposable data are destroyed, the confirmation token
+    /// must be the application's name. Returns the names of the removed volumes.
+    async fn destroy_app_containers(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        disposable_only: bool,
+        confirmation_token: Option<String>,
+    ) -> Result<Vec<String>> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        if !disposable_only && confirmation_token.as_ref() != Some(&runner.app().name) {
+            let has_persistent_volumes =
+                runner
+                    .app()
+                    .list_volumes(&state.runner_env)
+                    .iter()
+                    .any(|volume| {
+                        volume.storage == AppVolumeStorage::DockerVolume && !volume.disposable
+                    });
+
+            if has_persistent_volumes {
+                Err("Destroying non-disposable volumes requires the application's name as confirmation token")?;
+            }
+        }
+
+        let removed = runner.destroy(disposable_only).await?;
+
+        sync_desired_state(state, &[id], AppDesiredState::Absent);
+
+        Ok(removed)
+    }
+
+    /// Remove an application whose containers were destroyed
+    ///
+    /// Its data is either kept, the application being archived so it can be restored later, or purged.
+    /// Fails if other applications depend on it, unless cascading which stops them
+    /// and removes their dependency on this application.
+    async fn remove_app(
+        &self,
+        ctx: &Context<'_>,
+        id: AppId,
+        data_policy: AppDataPolicy,
+        cascade: Option<bool>,
+    ) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
+
+        let runner = get_runner_for(state, id).await?;
+
+        runner.ensure_can_be_removed().await?;
+
+        let dependents = state
+            .user_data()
+            .apps
+            .iter()
+            .filter(|app| app.dependencies.iter().any(|dep| dep.app_id == id))
+            .map(|app| format!("'{}'", app.name))
+            .collect::<Vec<_>>();
+
+        if !dependents.is_empty() && cascade != Some(true) {
+            Err(format!(
+                "Application is required by application(s): {}",
+                dependents.join(", ")
+            ))?;
+        }
+
+        let stopped = stop_running_dependents(state, id, true).await?;
+
+        if data_policy == AppDataPolicy::Purge {
+            runner.purge_data().await?;
+        }
+
+        sync_desired_state(state, &stopped, AppDesiredState::Stopped);
+
+        let mut user_data = state.user_data_mut();
+
+        for app in &mut user_data.apps {
+            app.dependencies.retain(|dep| dep.app_id != id);
+        }
+
+        let index = user_data.apps.iter().position(|app| app.id == id).expect("Assertion error: application was not found in user data after checking checked for removal");
+        let app = user_data.apps.remove(index);
+
+        if data_policy == AppDataPolicy::Keep {
+            user_data.archived_apps.push(ArchivedApp {
+                app,
+                archived_on: get_now(),
+            });
+        }
+
+        Ok(Void)
+    }
+
+    /// Restore an archived application, re-attaching its kept data
+    ///
+    /// Dependencies on applications that no longer exist are dropped.
+    async fn restore_archived_app(&self, ctx: &Context<'_>, id: AppId) -> Result<App> {
+        let state = &mut get_state(ctx).await;
+
+        let mut app = state
+            .user_data()
+            .archived_apps
+            .iter()
+            .find(|archived| archived.app.id == id)
+            .ok_or("Provided archived application ID was not found")?
+            .app
+            .clone();
+
+        if state
+            .user_data()
+            .apps
+            .iter()
+            .any(|other| other.name == app.name)
+        {
+            Err("An application already exists with the archived application's name")?;
+        }
+
+        state.docker_host_or_default(Some(&app.host))?;
+
+        app.check_global_paths(&state.user_data().global_paths)?;
+
+        app.dependencies.retain(|dep| {
+            state
+                .user_data()
+                .apps
+                .iter()
+                .any(|other| other.id == dep.app_id)
+        });
+
+        let mut apps = state.user_data().apps.clone();
+        apps.push(app.clone());
+
+        check_app_dependencies(&apps).context("Invalid dependencies in archived application")?;
+        check_desired_states(&apps).context("Conflicting desired states")?;
+
+        let mut user_data = state.user_data_mut();
+
+        user_data
+            .archived_apps
+            .retain(|archived| archived.app.id != id);
+        user_data.apps.push(app.clone());
+
+        Ok(app)
+    }
+
+    /// Delete the data of an archived application, which can then not be restored anymore
+    async fn purge_archived_app(&self, ctx: &Context<'_>, id: AppId) -> Result<Void> {
+        let state = &mut get_state(ctx).await;
 
-        let index = apps.iter().position(|app| app.id == id).expect("Assertion error: application was not found in user data after checking checked for removal");
-        apps.remove(index);
+        let archived = state
+            .user_data()
+            .archived_apps
+            .iter()
+            .find(|archived| archived.app.id == id)
+            .ok_or("Provided archived application ID was not found")?;
+
+        let host = state.docker_host_or_default(Some(&archived.app.host))?;
+
+        if !host.connection.connected {
+            Err(format!(
+                "Docker host '{}' is currently unreachable",
+                host.name
+            ))?;
+        }
+
+        AppRunner::new(
+            &host.client,
+            &state.runner_env,
+            state.user_data(),
+            &archived.app,
+        )
+        .purge_data()
+        .await?;
+
+        state
+            .user_data_mut()
+            .archived_apps
+            .retain(|archived| archived.app.id != id);
 
         Ok(Void)
     }
 }
+
+/// Stop managing an application with the reconciliation loop after a manual action on some of its containers
+///
+/// Otherwise the loop would revert the action, as it only handles whole applications.
+fn suspend_desired_state(state: &mut State, id: AppId) {
+    let mut user_data = state.user_data_mut();
+
+    let Some(app) = user_data
+        .apps
+        .iter_mut()
+        .find(|app| app.id == id && app.desired_state.is_some())
+    else {
+        return;
+    };
+
+    info!(
+        "Application '{}' is not managed by the reconciliation loop anymore after a manual action on its containers",
+        app.name
+    );
+
+    app.desired_state = None;
+}
+
+fn insert_new_app(
+    state: &mut State,
+    input: AppTemplate,
+    host: Option<String>,
+    source: Option<AppTemplateSource>,
+) -> Result<App> {
+    let host = state.docker_host_or_default(host.as_deref())?.name.clone();
+
+    if state
+        .user_data()
+        .apps
+        .iter()
+        .any(|app| app.name == input.name)
+    {
+        Err("An application already exists with the provided name")?;
+    }
+
+    let app = App::new(input, host, source, state.runner_env.secrets_key())
+        .context("Failed to create the application")?;
+
+    app.check_global_paths(&state.user_data().global_paths)?;
+
+    state.user_data_mut().apps.push(app.clone());
+
+    Ok(app)
+}
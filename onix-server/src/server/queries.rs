@@ -1,14 +1,26 @@
+use std::collections::HashMap;
+
 use anyhow::Context as _;
 use async_graphql::{ComplexObject, Context, Object};
 
 use crate::{
-    apps::{App, AppContainer, AppId, AppRunningStatus, AppTemplate, ExistingAppContainer},
-    docker,
+    apps::{
+        decode_template_params, parse_env_file, parse_template_version, App, AppContainer,
+        AppDependencyGraph, AppId, AppRunningStatus, AppTemplate, AppTemplateParam, AppVolumeInfo,
+        AppsFilter, AppsSort, ExistingAppContainer, GlobalPath, GlobalPathUsage, OutdatedApp,
+        ParsedEnvFile, REDACTED_VALUE,
+    },
+    data::ArchivedApp,
+    docker::{self, ContainerEnvironmentVar, ContainerPrivileges},
     stores::{StoreConfig, StoreContent, StoreInterface},
     utils::graphql::{CustomGraphQLError, Result},
 };
 
-use super::state::{get_runner_for, get_state};
+use super::{
+    docker_hosts::{AppsStatusCategory, AppsStatusCount, DockerHostSummary},
+    docker_supervisor::DockerHealth,
+    state::{get_apps_statuses, get_runner_for, get_state},
+};
 
 pub struct QueryRoot;
 
@@ -18,14 +30,158 @@ impl QueryRoot {
         env!("CARGO_PKG_VERSION")
     }
 
-    async fn docker_version(&self, ctx: &Context<'_>) -> Result<Option<String>> {
-        docker::docker_version(&get_state(ctx).await.docker)
+    async fn docker_version(
+        &self,
+        ctx: &Context<'_>,
+        host: Option<String>,
+    ) -> Result<Option<String>> {
+        let docker = get_state(ctx)
             .await
-            .map_err(Into::into)
+            .docker_host_or_default(host.as_deref())?
+            .client
+            .clone();
+
+        docker::docker_version(&docker).await.map_err(Into::into)
+    }
+
+    async fn docker_health(&self, ctx: &Context<'_>, host: Option<String>) -> Result<DockerHealth> {
+        let (docker, connection) = {
+            let state = get_state(ctx).await;
+            let host = state.docker_host_or_default(host.as_deref())?;
+
+            (host.client.clone(), host.connection.clone())
+        };
+
+        let daemon = if connection.connected {
+            docker::docker_daemon_infos(&docker)
+                .await
+                .map_err(|err| format!("{err:?}"))
+        } else {
+            Err("Docker daemon is unreachable".to_string())
+        };
+
+        let (daemon, daemon_error) = match daemon {
+            Ok(daemon) => (Some(daemon), None),
+            Err(err) => (None, Some(err)),
+        };
+
+        Ok(DockerHealth {
+            connection,
+            daemon,
+            daemon_error,
+        })
     }
 
-    async fn apps(&self, ctx: &Context<'_>) -> Vec<App> {
-        get_state(ctx).await.user_data().apps.clone()
+    async fn docker_hosts(&self, ctx: &Context<'_>) -> Vec<DockerHostSummary> {
+        let state = &get_state(ctx).await;
+        let statuses = get_apps_statuses(state).await;
+
+        let mut summaries = vec![];
+
+        for host in &state.docker_hosts {
+            let apps = state
+                .user_data()
+                .apps
+                .iter()
+                .filter(|app| app.host == host.name)
+                .collect::<Vec<_>>();
+
+            let mut counts = HashMap::<AppsStatusCategory, usize>::new();
+
+            for app in &apps {
+                let status = match statuses.get(&app.id) {
+                    Some(Ok(status)) => (*status).into(),
+                    Some(Err(_)) | None => AppsStatusCategory::Unknown,
+                };
+
+                *counts.entry(status).or_default() += 1;
+            }
+
+            summaries.push(DockerHostSummary {
+                name: host.name.clone(),
+                is_default: host.name == state.default_docker_host,
+                connection: host.connection.clone(),
+                apps_count: apps.len(),
+                apps_status: counts
+                    .into_iter()
+                    .map(|(status, count)| AppsStatusCount { status, count })
+                    .collect(),
+            });
+        }
+
+        summaries
+    }
+
+    /// List applications, sorted by name by default
+    async fn apps(
+        &self,
+        ctx: &Context<'_>,
+        host: Option<String>,
+        filter: Option<AppsFilter>,
+        sort: Option<AppsSort>,
+    ) -> Vec<App> {
+        let state = &get_state(ctx).await;
+        let filter = filter.unwrap_or_default();
+
+        // Statuses are only fetched when required, as it requires to list all Docker containers
+        let statuses = if filter.status.is_some() {
+            Some(get_apps_statuses(state).await)
+        } else {
+            None
+        };
+
+        let mut apps = state
+            .user_data()
+            .apps
+            .iter()
+            .filter(|app| match &host {
+                Some(host) => &app.host == host,
+                None => true,
+            })
+            .filter(|app| {
+                let status = statuses
+                    .as_ref()
+                    .and_then(|statuses| statuses.get(&app.id))
+                    .and_then(|status| status.as_ref().ok().copied());
+
+                filter.matches(app, status)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        sort.unwrap_or_default().sort(&mut apps);
+
+        apps
+    }
+
+    /// Applications removed while keeping their data
+    async fn archived_apps(&self, ctx: &Context<'_>) -> Vec<ArchivedApp> {
+        get_state(ctx).await.user_data().archived_apps.clone()
+    }
+
+    async fn global_paths(&self, ctx: &Context<'_>) -> Vec<GlobalPath> {
+        get_state(ctx).await.user_data().global_paths.clone()
+    }
+
+    /// List the applications using each global path
+    async fn global_paths_usage(&self, ctx: &Context<'_>) -> Vec<GlobalPathUsage> {
+        let state = &get_state(ctx).await;
+
+        state
+            .user_data()
+            .global_paths
+            .iter()
+            .map(|global_path| GlobalPathUsage {
+                global_path: global_path.clone(),
+                apps: state
+                    .user_data()
+                    .apps
+                    .iter()
+                    .filter(|app| app.used_global_paths().any(|used| used == global_path.id))
+                    .cloned()
+                    .collect(),
+            })
+            .collect()
     }
 
     async fn app(&self, ctx: &Context<'_>, id: AppId) -> Result<App, &'static str> {
@@ -50,6 +206,65 @@ impl QueryRoot {
         Ok(serde_yaml::from_str(&template).context("Failed to decode YAML template")?)
     }
 
+    async fn parse_env_file(&self, content: String) -> ParsedEnvFile {
+        parse_env_file(&content)
+    }
+
+    async fn template_params(&self, template: String) -> Result<Vec<AppTemplateParam>> {
+        decode_template_params(&template).map_err(Into::into)
+    }
+
+    /// List the applications for which a newer template is available in their store
+    async fn outdated_apps(&self, ctx: &Context<'_>) -> Result<Vec<OutdatedApp>> {
+        let origins = get_state(ctx)
+            .await
+            .user_data()
+            .apps
+            .iter()
+            .filter_map(|app| {
+                let origin = app.template_origin.clone()?;
+                let store_url = origin.store_url.clone()?;
+                Some((app.id, app.name.clone(), store_url, origin))
+            })
+            .collect::<Vec<_>>();
+
+        let mut stores = HashMap::<String, StoreContent>::new();
+        let mut outdated = vec![];
+
+        for (app_id, app_name, store_url, origin) in origins {
+            if !stores.contains_key(&store_url) {
+                let store = StoreInterface::new(StoreConfig {
+                    url: store_url.clone(),
+                })
+                .pull()
+                .await
+                .with_context(|| format!("Failed to pull store '{store_url}'"))?;
+
+                stores.insert(store_url.clone(), store);
+            }
+
+            let Some(latest_version) = stores[&store_url]
+                .find_template(&origin.template_id)
+                .and_then(|template| template.template_version())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+
+            if parse_template_version(&latest_version)? > parse_template_version(&origin.version)? {
+                outdated.push(OutdatedApp {
+                    app_id,
+                    app_name,
+                    template_id: origin.template_id,
+                    current_version: origin.version,
+                    latest_version,
+                });
+            }
+        }
+
+        Ok(outdated)
+    }
+
     async fn pull_store(&self, store_config: StoreConfig) -> Result<StoreContent> {
         StoreInterface::new(store_config)
             .pull()
@@ -67,6 +282,36 @@ impl App {
         runner.status().await.map_err(CustomGraphQLError::from)
     }
 
+    /// URL of the application's web interface, built from the address of its Docker host and the bound host port
+    async fn web_ui_url(&self, ctx: &Context<'_>) -> Option<String> {
+        let web_ui = self.metadata.web_ui.as_ref()?;
+
+        let host_port = self
+            .get_container(&web_ui.container)?
+            .port_bindings
+            .iter()
+            .find(|binding| {
+                binding.container_port.port == web_ui.port
+                    && binding.container_port.port_type.covers_tcp()
+            })?
+            .host_port
+            .port;
+
+        let state = &get_state(ctx).await;
+
+        Some(web_ui.url(state.published_ports_address(&self.host), host_port))
+    }
+
+    /// Volumes whose storage is managed by Onix, including shared ones
+    async fn volumes(&self, ctx: &Context<'_>) -> Vec<AppVolumeInfo> {
+        self.list_volumes(&get_state(ctx).await.runner_env)
+    }
+
+    async fn dependency_graph(&self) -> Result<AppDependencyGraph> {
+        AppDependencyGraph::new(&self.containers).map_err(Into::into)
+    }
+
+    /// Export the application as a YAML template, with the values of secret environment variables redacted
     async fn generate_template(&self, ctx: &Context<'_>) -> Result<String> {
         let state = &get_state(ctx).await;
         let runner = get_runner_for(state, self.id).await?;
@@ -78,6 +323,37 @@ impl App {
 
 #[ComplexObject]
 impl AppContainer {
+    /// Environment variables, with the value of secret ones being redacted
+    async fn env_vars(&self) -> Vec<ContainerEnvironmentVar> {
+        self.env_vars
+            .iter()
+            .map(|var| ContainerEnvironmentVar {
+                name: var.name.clone(),
+                value: if var.secret == Some(true) {
+                    REDACTED_VALUE.to_string()
+                } else {
+                    var.value.clone()
+                },
+                secret: var.secret,
+            })
+            .collect()
+    }
+
+    /// Indicates if the container runs with elevated privileges
+    async fn is_elevated(&self) -> bool {
+        self.privileges
+            .as_ref()
+            .is_some_and(|privileges| !privileges.elevation_reasons().is_empty())
+    }
+
+    /// Reasons for which the container is considered to be running with elevated privileges
+    async fn elevation_reasons(&self) -> Vec<String> {
+        self.privileges
+            .as_ref()
+            .map(ContainerPrivileges::elevation_reasons)
+            .unwrap_or_default()
+    }
+
     async fn docker_container(&self, ctx: &Context<'_>) -> Result<Option<ExistingAppContainer>> {
         let state = &get_state(ctx).await;
         let runner = get_runner_for(state, self.app.id).await?;
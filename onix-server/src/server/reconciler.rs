@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use log::{debug, error, info, warn};
+use tokio::time::sleep;
+
+use crate::apps::{AppDesiredState, AppId, AppRunningStatus};
+
+use super::state::{
+    get_apps_statuses, get_runner_for, start_app_with_dependencies, stop_running_dependents,
+    sync_started_desired_state, AppsContext, WrappedState,
+};
+
+static LOGGER_TARGET: &str = "reconciler";
+static RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+static MIN_RETRY_DELAY: Duration = Duration::from_secs(30);
+static MAX_RETRY_DELAY: Duration = Duration::from_secs(30 * 60);
+
+/// Failed attempts to bring an application to its desired state
+struct Backoff {
+    failures: u32,
+    retry_after: Instant,
+}
+
+/// A loop bringing the applications with a desired state to it
+///
+/// Applications that fail to converge are retried with an exponential backoff.
+/// Applications on unreachable Docker hosts are skipped until the connection is restored.
+///
+/// Docker operations are performed on snapshots of the state, so the server isn't blocked meanwhile.
+pub async fn reconciler(state: WrappedState) -> ! {
+    let mut backoffs = HashMap::<AppId, Backoff>::new();
+
+    loop {
+        let snapshot = state.lock().await.snapshot();
+        let statuses = get_apps_statuses(&snapshot).await;
+
+        let pending = snapshot
+            .user_data()
+            .apps
+            .iter()
+            .filter_map(|app| {
+                let desired = app.desired_state?;
+
+                match statuses.get(&app.id) {
+                    Some(Ok(status)) if needs_action(desired, *status) => {
+                        Some((app.id, app.name.clone(), desired))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Applications that converged (or were removed) start over with a clean slate
+        backoffs.retain(|id, _| pending.iter().any(|(pending_id, _, _)| pending_id == id));
+
+        for (id, name, desired) in pending {
+            if backoffs
+                .get(&id)
+                .is_some_and(|backoff| backoff.retry_after > Instant::now())
+            {
+                debug!(target: LOGGER_TARGET, "| Application '{name}' is backing off, skipping.");
+                continue;
+            }
+
+            let result = converge(&state, id, desired).await;
+
+            match result {
+                Ok(()) => {
+                    backoffs.remove(&id);
+                }
+
+                Err(err) => {
+                    let backoff = backoffs.entry(id).or_insert(Backoff {
+                        failures: 0,
+                        retry_after: Instant::now(),
+                    });
+
+                    backoff.failures += 1;
+
+                    let delay = (MIN_RETRY_DELAY * 2_u32.pow((backoff.failures - 1).min(10)))
+                        .min(MAX_RETRY_DELAY);
+
+                    backoff.retry_after = Instant::now() + delay;
+
+                    error!(
+                        target: LOGGER_TARGET,
+                        "Failed to bring application '{name}' to state {desired:?}: {err:?}"
+                    );
+
+                    warn!(
+                        target: LOGGER_TARGET,
+                        "Retrying for application '{name}' in {} second(s)...",
+                        delay.as_secs()
+                    );
+                }
+            }
+        }
+
+        sleep(RECONCILE_INTERVAL).await;
+    }
+}
+
+/// Check if an action is required to bring an application to its desired state
+///
+/// Applications in an intermediary or zombie state are left alone until they settle.
+fn needs_action(desired: AppDesiredState, status: AppRunningStatus) -> bool {
+    match status {
+        AppRunningStatus::Zombie | AppRunningStatus::Intermediary => false,
+
+        AppRunningStatus::NotCreated => desired == AppDesiredState::Running,
+
+        AppRunningStatus::PartiallyCreated | AppRunningStatus::PartiallyRunning => true,
+
+        AppRunningStatus::Stopped => desired != AppDesiredState::Stopped,
+
+        AppRunningStatus::FullyRunning => desired != AppDesiredState::Running,
+    }
+}
+
+async fn converge(state: &WrappedState, id: AppId, desired: AppDesiredState) -> Result<()> {
+    let snapshot = state.lock().await.snapshot();
+
+    let runner = get_runner_for(&snapshot, id)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    let app = runner.app();
+
+    // The desired state may have changed since the check
+    if app.desired_state != Some(desired) {
+        return Ok(());
+    }
+
+    let status = runner.status().await?;
+
+    match desired {
+        AppDesiredState::Running => {
+            match status {
+                AppRunningStatus::NotCreated => {
+                    info!(target: LOGGER_TARGET, "Creating containers of application '{}'...", app.name);
+                    runner.create_containers().await?;
+                }
+
+                AppRunningStatus::PartiallyCreated => {
+                    info!(target: LOGGER_TARGET, "Creating missing containers of application '{}'...", app.name);
+                    runner.create_missing_containers().await?;
+                }
+
+                AppRunningStatus::Stopped | AppRunningStatus::PartiallyRunning => {}
+
+                AppRunningStatus::FullyRunning
+                | AppRunningStatus::Zombie
+                | AppRunningStatus::Intermediary => return Ok(()),
+            }
+
+            info!(target: LOGGER_TARGET, "Starting application '{}'...", app.name);
+            let started = start_app_with_dependencies(&snapshot, id).await?;
+
+            let mut state = state.lock().await;
+
+            // Unless the desired state changed while starting
+            if state
+                .user_data()
+                .apps
+                .iter()
+                .any(|app| app.id == id && app.desired_state == Some(desired))
+            {
+                sync_started_desired_state(&mut state, &started);
+            }
+        }
+
+        AppDesiredState::Stopped => match status {
+            AppRunningStatus::PartiallyRunning | AppRunningStatus::FullyRunning => {
+                info!(target: LOGGER_TARGET, "Stopping application '{}'...", app.name);
+                stop_running_dependents(&snapshot, id, false).await?;
+                runner.stop().await?;
+            }
+
+            AppRunningStatus::PartiallyCreated => {
+                info!(target: LOGGER_TARGET, "Creating missing containers of application '{}'...", app.name);
+                runner.create_missing_containers().await?;
+            }
+
+            AppRunningStatus::NotCreated
+            | AppRunningStatus::Stopped
+            | AppRunningStatus::Zombie
+            | AppRunningStatus::Intermediary => {}
+        },
+
+        AppDesiredState::Absent => match status {
+            AppRunningStatus::PartiallyRunning | AppRunningStatus::FullyRunning => {
+                info!(target: LOGGER_TARGET, "Stopping application '{}' to remove its containers...", app.name);
+                stop_running_dependents(&snapshot, id, false).await?;
+                runner.stop().await?;
+
+                info!(target: LOGGER_TARGET, "Removing containers of application '{}'...", app.name);
+                runner.remove_containers().await?;
+            }
+
+            AppRunningStatus::PartiallyCreated | AppRunningStatus::Stopped => {
+                info!(target: LOGGER_TARGET, "Removing containers of application '{}'...", app.name);
+                runner.remove_containers().await?;
+            }
+
+            AppRunningStatus::NotCreated
+            | AppRunningStatus::Zombie
+            | AppRunningStatus::Intermediary => {}
+        },
+    }
+
+    Ok(())
+}
@@ -1,16 +1,22 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{bail, Context as _, Result};
 use async_graphql::Context;
-use bollard::Docker;
+use log::{info, warn};
 use tokio::sync::{Mutex, MutexGuard};
 
 use crate::{
-    apps::{AppId, AppRunner, AppRunnerConfig, AppRunnerEnvironment},
+    apps::{
+        list_existing_app_containers, sorted_app_dependencies, sorted_app_dependents, App,
+        AppDesiredState, AppId, AppRunner, AppRunnerConfig, AppRunnerEnvironment, AppRunningStatus,
+    },
     data::UserData,
 };
 
-use super::user_data::{UserDataSaver, UserDataSavingState, WritableUserData};
+use super::{
+    docker_hosts::DockerHost,
+    user_data::{UserDataSaver, UserDataSavingState, WritableUserData},
+};
 
 /// The application's state
 pub struct State {
@@ -20,8 +26,14 @@ pub struct State {
     /// Address the server is running on
     pub address: String,
 
-    /// Docker API client
-    pub docker: Docker,
+    /// Hostname the local machine is reachable at
+    pub public_hostname: Option<String>,
+
+    /// Docker hosts applications can run on
+    pub docker_hosts: Vec<DockerHost>,
+
+    /// Name of the Docker host used when none is specified
+    pub default_docker_host: String,
 
     /// Runner environment
     pub runner_env: AppRunnerEnvironment,
@@ -41,21 +53,80 @@ impl State {
         #[deny(unused_variables)] StateConfig {
             port,
             address,
-            docker,
+            public_hostname,
+            docker_hosts,
+            default_docker_host,
             user_data,
             user_data_saver,
             runner_config,
         }: StateConfig,
     ) -> State {
+        let mut user_data = user_data.unwrap_or_default();
+
+        let user_data_saving_state = if user_data.fill_missing_hosts(&default_docker_host) {
+            info!(
+                "Bound applications without a Docker host to default host '{default_docker_host}'"
+            );
+            UserDataSavingState::Modified
+        } else {
+            UserDataSavingState::Unchanged
+        };
+
+        for app in &user_data.apps {
+            if !docker_hosts.iter().any(|host| host.name == app.host) {
+                warn!(
+                    "Application '{}' is bound to undeclared Docker host '{}' and cannot be managed",
+                    app.name, app.host
+                );
+            }
+        }
+
         State {
             port,
             address,
-            docker,
+            public_hostname,
+            docker_hosts,
+            default_docker_host,
             runner_env: AppRunnerEnvironment::new(runner_config),
             user_data_saver,
-            user_data_saving_state: UserDataSavingState::Unchanged,
+            user_data_saving_state,
+
+            user_data,
+        }
+    }
+
+    pub fn docker_host(&self, name: &str) -> Option<&DockerHost> {
+        self.docker_hosts.iter().find(|host| host.name == name)
+    }
+
+    pub fn docker_host_mut(&mut self, name: &str) -> Option<&mut DockerHost> {
+        self.docker_hosts.iter_mut().find(|host| host.name == name)
+    }
 
-            user_data: user_data.unwrap_or_default(),
+    /// Get a Docker host from its name, or the default one if none is provided
+    pub fn docker_host_or_default(&self, name: Option<&str>) -> Result<&DockerHost, String> {
+        let name = name.unwrap_or(&self.default_docker_host);
+
+        self.docker_host(name)
+            .ok_or_else(|| format!("Unknown Docker host '{name}'"))
+    }
+
+    /// Get the address the ports published on a Docker host are reachable at
+    pub fn published_ports_address(&self, docker_host: &str) -> &str {
+        if let Some(address) = self
+            .docker_host(docker_host)
+            .and_then(|host| host.remote_address.as_deref())
+        {
+            return address;
+        }
+
+        if let Some(hostname) = &self.public_hostname {
+            return hostname;
+        }
+
+        match self.address.as_str() {
+            "0.0.0.0" | "::" => "localhost",
+            address => address,
         }
     }
 
@@ -66,13 +137,87 @@ impl State {
     pub fn user_data_mut(&mut self) -> WritableUserData {
         WritableUserData::new(&mut self.user_data, &mut self.user_data_saving_state)
     }
+
+    /// Copy the parts of the state required to run applications
+    pub fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            docker_hosts: self.docker_hosts.clone(),
+            runner_env: self.runner_env.clone(),
+            user_data: self.user_data.clone(),
+        }
+    }
+}
+
+/// Copy of the parts of the state required to run applications
+///
+/// Allows running long operations (e.g. pulling images) without holding the state's lock.
+/// Modifications must be made on the state itself, after checking they still apply.
+pub struct StateSnapshot {
+    docker_hosts: Vec<DockerHost>,
+    runner_env: AppRunnerEnvironment,
+    user_data: UserData,
+}
+
+/// Parts of the state required to run applications, shared by the state and its snapshots
+pub trait AppsContext {
+    fn docker_hosts(&self) -> &[DockerHost];
+    fn runner_env(&self) -> &AppRunnerEnvironment;
+    fn user_data(&self) -> &UserData;
+
+    fn docker_host(&self, name: &str) -> Option<&DockerHost> {
+        self.docker_hosts().iter().find(|host| host.name == name)
+    }
+}
+
+impl AppsContext for State {
+    fn docker_hosts(&self) -> &[DockerHost] {
+        &self.docker_hosts
+    }
+
+    fn runner_env(&self) -> &AppRunnerEnvironment {
+        &self.runner_env
+    }
+
+    fn user_data(&self) -> &UserData {
+        &self.user_data
+    }
+}
+
+impl AppsContext for MutexGuard<'_, State> {
+    fn docker_hosts(&self) -> &[DockerHost] {
+        &self.docker_hosts
+    }
+
+    fn runner_env(&self) -> &AppRunnerEnvironment {
+        &self.runner_env
+    }
+
+    fn user_data(&self) -> &UserData {
+        &self.user_data
+    }
+}
+
+impl AppsContext for StateSnapshot {
+    fn docker_hosts(&self) -> &[DockerHost] {
+        &self.docker_hosts
+    }
+
+    fn runner_env(&self) -> &AppRunnerEnvironment {
+        &self.runner_env
+    }
+
+    fn user_data(&self) -> &UserData {
+        &self.user_data
+    }
 }
 
 /// Configuration object used to generate a state
 pub struct StateConfig {
     pub port: u16,
     pub address: String,
-    pub docker: Docker,
+    pub public_hostname: Option<String>,
+    pub docker_hosts: Vec<DockerHost>,
+    pub default_docker_host: String,
     pub user_data: Option<UserData>,
     pub user_data_saver: UserDataSaver,
     pub runner_config: AppRunnerConfig,
@@ -103,13 +248,246 @@ pub async fn get_state<'a>(context: &'a Context<'_>) -> MutexGuard<'a, State> {
 }
 
 /// Generate a runner for a specific application
-pub async fn get_runner_for(state: &State, id: AppId) -> Result<AppRunner, String> {
+pub async fn get_runner_for(state: &impl AppsContext, id: AppId) -> Result<AppRunner, String> {
     let app = state
-        .user_data
+        .user_data()
         .apps
         .iter()
         .find(|app| app.id == id)
         .ok_or("Provided application ID was not found")?;
 
-    Ok(AppRunner::new(&state.docker, &state.runner_env, app))
+    let host = state
+        .docker_host(&app.host)
+        .ok_or_else(|| format!("Application is bound to unknown Docker host '{}'", app.host))?;
+
+    if !host.connection.connected {
+        return Err(format!(
+            "Docker host '{}' is currently unreachable: {}",
+            host.name,
+            host.connection
+                .last_error
+                .as_deref()
+                .unwrap_or("unknown error")
+        ));
+    }
+
+    Ok(AppRunner::new(
+        &host.client,
+        state.runner_env(),
+        state.user_data(),
+        app,
+    ))
+}
+
+/// Get the status of all applications, listing the containers of each Docker host only once
+pub async fn get_apps_statuses(
+    state: &impl AppsContext,
+) -> HashMap<AppId, Result<AppRunningStatus, String>> {
+    let mut statuses = HashMap::new();
+
+    for host in state.docker_hosts() {
+        let existing = if host.connection.connected {
+            list_existing_app_containers(&host.client)
+                .await
+                .map_err(|err| format!("{err:?}"))
+        } else {
+            Err(format!(
+                "Docker host '{}' is currently unreachable",
+                host.name
+            ))
+        };
+
+        for app in state
+            .user_data()
+            .apps
+            .iter()
+            .filter(|app| app.host == host.name)
+        {
+            let status = match &existing {
+                Ok(existing) => {
+                    Ok(
+                        AppRunner::new(&host.client, state.runner_env(), state.user_data(), app)
+                            .status_from(existing),
+                    )
+                }
+                Err(err) => Err(err.clone()),
+            };
+
+            statuses.insert(app.id, status);
+        }
+    }
+
+    for app in &state.user_data().apps {
+        statuses.entry(app.id).or_insert_with(|| {
+            Err(format!(
+                "Application is bound to unknown Docker host '{}'",
+                app.host
+            ))
+        });
+    }
+
+    statuses
+}
+
+/// Start an application after the applications it depends on, directly or not
+///
+/// Dependencies sharing their network are exposed on it before their dependents are started.
+/// Returns the identifiers of the applications that are now running, the provided one being the last.
+pub async fn start_app_with_dependencies(
+    state: &impl AppsContext,
+    id: AppId,
+) -> Result<Vec<AppId>> {
+    let apps = &state.user_data().apps;
+
+    let mut to_start = sorted_app_dependencies(apps, id)?;
+    to_start.push(
+        apps.iter()
+            .find(|app| app.id == id)
+            .context("Provided application ID was not found")?,
+    );
+
+    let mut started = vec![];
+
+    for app in to_start {
+        for dep in &app.dependencies {
+            if dep.shared_network == Some(true) {
+                get_runner_for(state, dep.app_id)
+                    .await
+                    .map_err(anyhow::Error::msg)?
+                    .expose_on_shared_network()
+                    .await?;
+            }
+        }
+
+        let runner = get_runner_for(state, app.id)
+            .await
+            .map_err(anyhow::Error::msg)?;
+
+        if app.id != id {
+            info!("Starting application '{}' as a dependency...", app.name);
+        }
+
+        runner
+            .start()
+            .await
+            .with_context(|| format!("Failed to start application '{}'", app.name))?;
+
+        started.push(app.id);
+    }
+
+    Ok(started)
+}
+
+/// Ensure no application depending on the provided one is running, or stop them if cascading
+///
+/// Returns the identifiers of the stopped applications.
+pub async fn stop_running_dependents(
+    state: &impl AppsContext,
+    id: AppId,
+    cascade: bool,
+) -> Result<Vec<AppId>> {
+    let dependents = sorted_app_dependents(&state.user_data().apps, id)?;
+
+    if dependents.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let statuses = get_apps_statuses(state).await;
+
+    let running = dependents
+        .into_iter()
+        .filter(|app| {
+            !matches!(
+                statuses.get(&app.id),
+                Some(Ok(AppRunningStatus::NotCreated | AppRunningStatus::Stopped))
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if running.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if !cascade {
+        bail!(
+            "Application is required by running (or unreachable) application(s): {}",
+            running
+                .iter()
+                .map(|app| format!("'{}'", app.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let mut stopped = vec![];
+
+    for app in running {
+        info!("Stopping dependent application '{}'...", app.name);
+
+        get_runner_for(state, app.id)
+            .await
+            .map_err(anyhow::Error::msg)?
+            .stop()
+            .await
+            .with_context(|| format!("Failed to stop dependent application '{}'", app.name))?;
+
+        stopped.push(app.id);
+    }
+
+    Ok(stopped)
+}
+
+/// Keep the desired state of applications managed by the reconciliation loop in line with a manual action
+///
+/// Otherwise the loop would revert the action.
+pub fn sync_desired_state(state: &mut State, ids: &[AppId], desired: AppDesiredState) {
+    let is_outdated = |app: &App| {
+        ids.contains(&app.id) && app.desired_state.is_some_and(|current| current != desired)
+    };
+
+    if !state.user_data.apps.iter().any(is_outdated) {
+        return;
+    }
+
+    for app in &mut state.user_data_mut().apps {
+        if is_outdated(app) {
+            app.desired_state = Some(desired);
+        }
+    }
+}
+
+/// Keep the desired states in line with the start of an application along with its dependencies
+///
+/// Takes the applications returned by [`start_app_with_dependencies`]. If the started application
+/// is managed by the reconciliation loop, so become the dependencies it required.
+pub fn sync_started_desired_state(state: &mut State, started: &[AppId]) {
+    sync_desired_state(state, started, AppDesiredState::Running);
+
+    let Some((id, dependencies)) = started.split_last() else {
+        return;
+    };
+
+    let is_managed = state
+        .user_data
+        .apps
+        .iter()
+        .any(|app| app.id == *id && app.desired_state.is_some());
+
+    let is_unmanaged_dep =
+        |app: &App| dependencies.contains(&app.id) && app.desired_state.is_none();
+
+    if !is_managed || !state.user_data.apps.iter().any(is_unmanaged_dep) {
+        return;
+    }
+
+    for app in &mut state.user_data_mut().apps {
+        if is_unmanaged_dep(app) {
+            info!(
+                "Application '{}' is now desired to be running, as a dependency of a managed application",
+                app.name
+            );
+
+            app.desired_state = Some(AppDesiredState::Running);
+        }
+    }
 }
@@ -4,30 +4,43 @@ use lz4_flex::{compress_prepend_size, decompress_size_prepended};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
-use crate::{apps::AppTemplate, utils::time::get_now};
+use crate::utils::time::get_now;
+
+use super::StoreTemplate;
 
 pub static MAX_STORE_SIZE_MB: usize = 10;
 
 #[derive(SimpleObject, Serialize, Deserialize)]
 pub struct StoreContent {
-    apps: Vec<AppTemplate>,
+    apps: Vec<StoreTemplate>,
 
     #[serde(default = "get_now")]
     created_on: OffsetDateTime,
 }
 
 impl StoreContent {
-    pub fn new(apps: Vec<AppTemplate>) -> Self {
+    pub fn new(apps: Vec<StoreTemplate>) -> Self {
         Self {
             apps,
             created_on: get_now(),
         }
     }
 
-    pub fn list_apps(&self) -> &[AppTemplate] {
+    pub fn list_apps(&self) -> &[StoreTemplate] {
         &self.apps
     }
 
+    pub fn list_apps_mut(&mut self) -> &mut [StoreTemplate] {
+        &mut self.apps
+    }
+
+    /// Find a template by its identifier
+    pub fn find_template(&self, id: &str) -> Option<&StoreTemplate> {
+        self.apps
+            .iter()
+            .find(|template| template.template_id() == Some(id))
+    }
+
     pub fn compress(&self) -> Result<Vec<u8>> {
         let ser = serde_yaml::to_string(self)
             .context("Failed to serialize the provided store as YAML")?;
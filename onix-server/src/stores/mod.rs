@@ -1,14 +1,15 @@
 mod content;
+mod template;
 
 use anyhow::{anyhow, Context, Result};
 use async_graphql::{InputObject, SimpleObject};
 
-pub use self::content::StoreContent;
+pub use self::{content::StoreContent, template::StoreTemplate};
 
 #[derive(SimpleObject, InputObject)]
 #[graphql(input_name_suffix = "Input")]
 pub struct StoreConfig {
-    url: String,
+    pub url: String,
 }
 
 pub struct StoreInterface {
@@ -20,6 +21,10 @@ impl StoreInterface {
         Self { url: config.url }
     }
 
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
     pub async fn pull(&self) -> Result<StoreContent> {
         let resp = reqwest::get(&self.url)
             .await
@@ -0,0 +1,69 @@
+use anyhow::Context;
+use async_graphql::Object;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use crate::{
+    apps::{decode_template_params, AppTemplateParam},
+    utils::graphql::Result,
+};
+
+/// Template of a store, kept as raw YAML until its parameters are substituted
+///
+/// Parameters can be referenced in typed fields (e.g. '${params.web_port}' as a host port),
+/// so the template can only be decoded once their values are known.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+pub struct StoreTemplate {
+    raw: Value,
+}
+
+impl StoreTemplate {
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.raw.get(key).and_then(Value::as_str)
+    }
+
+    pub fn template_id(&self) -> Option<&str> {
+        self.get_str("id")
+    }
+
+    pub fn template_version(&self) -> Option<&str> {
+        self.get_str("version")
+    }
+
+    pub fn raw_mut(&mut self) -> &mut Value {
+        &mut self.raw
+    }
+
+    /// Serialize the template, to be resolved with the values of its parameters
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        serde_yaml::to_string(&self.raw).context("Failed to serialize the store's template")
+    }
+}
+
+#[Object]
+impl StoreTemplate {
+    async fn name(&self) -> Option<&str> {
+        self.get_str("name")
+    }
+
+    /// Identifier of the template, unique in its store
+    async fn id(&self) -> Option<&str> {
+        self.template_id()
+    }
+
+    /// Version of the template (semver)
+    async fn version(&self) -> Option<&str> {
+        self.template_version()
+    }
+
+    /// Parameters to provide when creating an application from this template
+    async fn params(&self) -> Result<Vec<AppTemplateParam>> {
+        Ok(decode_template_params(&self.to_yaml()?)?)
+    }
+
+    /// YAML template, to provide to 'createAppFromTemplate' along with the parameters' values
+    async fn yaml(&self) -> Result<String> {
+        Ok(self.to_yaml()?)
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    fs,
+    os::unix::fs::{chown, symlink, PermissionsExt},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+/// Recursively copy a directory's content, preserving permissions and symbolic links
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)
+        .with_context(|| format!("Failed to create directory '{}'", to.display()))?;
+
+    fs::set_permissions(
+        to,
+        fs::metadata(from)
+            .with_context(|| format!("Failed to get metadata of '{}'", from.display()))?
+            .permissions(),
+    )
+    .with_context(|| format!("Failed to set permissions of '{}'", to.display()))?;
+
+    for entry in fs::read_dir(from)
+        .with_context(|| format!("Failed to read directory '{}'", from.display()))?
+    {
+        let entry =
+            entry.with_context(|| format!("Failed to read directory '{}'", from.display()))?;
+
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("Failed to get type of '{}'", source.display()))?;
+
+        if file_type.is_dir() {
+            copy_dir(&source, &target)?;
+        } else if file_type.is_symlink() {
+            let link = fs::read_link(&source)
+                .with_context(|| format!("Failed to read symbolic link '{}'", source.display()))?;
+
+            symlink(&link, &target).with_context(|| {
+                format!("Failed to create symbolic link '{}'", target.display())
+            })?;
+        } else {
+            fs::copy(&source, &target).with_context(|| {
+                format!(
+                    "Failed to copy '{}' to '{}'",
+                    source.display(),
+                    target.display()
+                )
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the owner, group and permissions of a file or directory (when provided)
+pub fn apply_ownership(
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    mode: Option<u32>,
+) -> Result<()> {
+    if uid.is_some() || gid.is_some() {
+        chown(path, uid, gid)
+            .with_context(|| format!("Failed to change the owner of '{}'", path.display()))?;
+    }
+
+    if let Some(mode) = mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+            .with_context(|| format!("Failed to set permissions of '{}'", path.display()))?;
+    }
+
+    Ok(())
+}